@@ -0,0 +1,2 @@
+Copies its input to its output and stops at the end of input when EOF stores zero
+,[.,]
@@ -0,0 +1,340 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+
+use brainfuck::frontend::{Instruction, Location, Program};
+
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", count, noun),
+    }
+}
+
+/// Something `check` found in a program, at the index of an instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Finding {
+    /// The loop starting here is only ever reached with the current cell at zero.
+    DeadLoop(usize),
+    /// This move certainly takes the memory pointer left of the first cell.
+    Underflow(usize),
+    /// The loop starting here moves the memory pointer by this many cells per iteration, or by an
+    /// amount that varies if `None`.
+    UnbalancedLoop(usize, Option<isize>),
+}
+
+impl Finding {
+    fn instruction(&self) -> usize {
+        match *self {
+            Finding::DeadLoop(instruction)
+            | Finding::Underflow(instruction)
+            | Finding::UnbalancedLoop(instruction, _) => instruction,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, Finding::Underflow(_))
+    }
+
+    fn message(&self) -> String {
+        match *self {
+            Finding::DeadLoop(_) => {
+                "warning: loop never runs, the current cell is always zero here".to_string()
+            }
+            Finding::Underflow(_) => "error: the memory pointer moves left of cell 0".to_string(),
+            Finding::UnbalancedLoop(_, Some(movement)) => format!(
+                "note: loop moves the memory pointer {} {} per iteration",
+                if movement < 0isize {
+                    "left by"
+                } else {
+                    "right by"
+                },
+                plural(movement.unsigned_abs(), "cell")
+            ),
+            Finding::UnbalancedLoop(_, None) => {
+                "note: loop moves the memory pointer by a varying amount per iteration".to_string()
+            }
+        }
+    }
+}
+
+/// What a program does to the tape, as far as can be told without running it.
+pub struct Analysis {
+    /// Findings in program order.
+    pub findings: Vec<Finding>,
+    pub loops: usize,
+    pub balanced_loops: usize,
+    /// Number of cells the program can reach, if the memory pointer is known everywhere.
+    pub tape_size: Option<usize>,
+}
+
+impl Analysis {
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(Finding::is_error)
+    }
+
+    /// The findings as diagnostics pointing into `source`, followed by a summary, which gives the
+    /// tape size needed unless the program underflows.
+    pub fn report(&self, program: &Program, path: &str, source: &[u8]) -> String {
+        let mut report: String = String::new();
+
+        for finding in &self.findings {
+            let location: Location =
+                Location::from_offset(source, program.offsets[finding.instruction()]);
+            let gutter: String = " ".repeat(location.line.to_string().len());
+            writeln!(
+                report,
+                "{}\n{gutter}--> {}:{}\n{}\n",
+                finding.message(),
+                path,
+                location,
+                location.snippet(source),
+            )
+            .unwrap();
+        }
+
+        let dead_loops: usize = self
+            .findings
+            .iter()
+            .filter(|finding| matches!(finding, Finding::DeadLoop(_)))
+            .count();
+        writeln!(
+            report,
+            "{}, {} with balanced pointer movement, {} never run",
+            plural(self.loops, "loop"),
+            self.balanced_loops,
+            dead_loops
+        )
+        .unwrap();
+        // No tape is big enough for a program that leaves it on the left.
+        if self.has_errors() {
+            return report;
+        }
+        match self.tape_size {
+            Some(tape_size) => writeln!(
+                report,
+                "the memory pointer stays within cells 0 to {}, so a tape of {} is enough",
+                tape_size - 1usize,
+                plural(tape_size, "cell")
+            ),
+            None => writeln!(
+                report,
+                "the tape size needed is unknown, since the memory pointer moves by amounts only known at runtime"
+            ),
+        }
+        .unwrap();
+
+        report
+    }
+}
+
+/// The effect of one iteration of a loop body.
+struct Body {
+    /// Net pointer movement, or `None` if a nested loop or call makes it vary.
+    movement: Option<isize>,
+    /// Cells the body may change, relative to where it starts.
+    touched: BTreeSet<isize>,
+}
+
+fn summarize(program: &Program, start: usize) -> Body {
+    let varying: Body = Body {
+        movement: None,
+        touched: BTreeSet::new(),
+    };
+    let mut offset: isize = 0isize;
+    let mut touched: BTreeSet<isize> = BTreeSet::new();
+    let mut i: usize = start + 1usize;
+
+    while i < program.jump_table[start] {
+        match program.instructions[i] {
+            Instruction::MoveRight => offset += 1isize,
+            Instruction::MoveLeft => offset -= 1isize,
+            Instruction::Increment | Instruction::Decrement | Instruction::Input => {
+                touched.insert(offset);
+            }
+            Instruction::LoopStart => {
+                let inner: Body = summarize(program, i);
+                if inner.movement != Some(0isize) {
+                    return varying;
+                }
+                touched.extend(inner.touched.iter().map(|&cell| offset + cell));
+                i = program.jump_table[i];
+            }
+            // A definition runs nothing.
+            Instruction::ProcedureStart => i = program.jump_table[i],
+            Instruction::Call => return varying,
+            _ => (),
+        }
+        i += 1usize;
+    }
+
+    Body {
+        movement: Some(offset),
+        touched,
+    }
+}
+
+/// What is known about the tape at one point in the program.
+#[derive(Clone)]
+struct State {
+    /// Whether `pointer` is a cell index, rather than relative to wherever a loop that moves the
+    /// pointer left it.
+    absolute: bool,
+    pointer: isize,
+    /// Cells whose value is known, or known to be unknown.
+    values: HashMap<isize, Option<i64>>,
+    /// Whether cells missing from `values` are still zero.
+    zeroed: bool,
+}
+
+impl State {
+    fn start() -> State {
+        State {
+            absolute: true,
+            pointer: 0isize,
+            values: HashMap::new(),
+            zeroed: true,
+        }
+    }
+
+    /// Nothing known, after code that moves the pointer by an unknown amount.
+    fn unknown() -> State {
+        State {
+            absolute: false,
+            pointer: 0isize,
+            values: HashMap::new(),
+            zeroed: false,
+        }
+    }
+
+    fn value(&self, cell: isize) -> Option<i64> {
+        match self.values.get(&cell) {
+            Some(&value) => value,
+            None => self.zeroed.then_some(0i64),
+        }
+    }
+
+    fn add(&mut self, amount: i64) {
+        let value: Option<i64> = self.value(self.pointer).map(|value| value + amount);
+        self.values.insert(self.pointer, value);
+    }
+
+    fn forget(&mut self, touched: &BTreeSet<isize>) {
+        for &cell in touched {
+            self.values.insert(self.pointer + cell, None);
+        }
+    }
+}
+
+struct Analyzer<'a> {
+    program: &'a Program,
+    findings: Vec<Finding>,
+    max_pointer: isize,
+    tape_size_known: bool,
+    underflowed: bool,
+}
+
+impl Analyzer<'_> {
+    fn moved(&mut self, state: &State, instruction: usize, certain: bool) {
+        if !state.absolute {
+            return;
+        }
+        if state.pointer < 0isize && certain && !self.underflowed {
+            self.findings.push(Finding::Underflow(instruction));
+            self.underflowed = true;
+        }
+        self.max_pointer = self.max_pointer.max(state.pointer);
+    }
+
+    fn lose_track(&mut self, state: &mut State) {
+        if state.absolute {
+            self.tape_size_known = false;
+        }
+        *state = State::unknown();
+    }
+
+    /// Follows the instructions from `start` up to `end`, which run whenever they are reached if
+    /// `certain`.
+    fn block(&mut self, start: usize, end: usize, state: &mut State, certain: bool) {
+        let mut i: usize = start;
+
+        while i < end {
+            match self.program.instructions[i] {
+                Instruction::Increment => state.add(1i64),
+                Instruction::Decrement => state.add(-1i64),
+                Instruction::Input => {
+                    state.values.insert(state.pointer, None);
+                }
+                Instruction::MoveRight => {
+                    state.pointer += 1isize;
+                    self.moved(state, i, certain);
+                }
+                Instruction::MoveLeft => {
+                    state.pointer -= 1isize;
+                    self.moved(state, i, certain);
+                }
+                Instruction::LoopStart => {
+                    let loop_end: usize = self.program.jump_table[i];
+                    let value: Option<i64> = state.value(state.pointer);
+
+                    if value == Some(0i64) {
+                        self.findings.push(Finding::DeadLoop(i));
+                    } else {
+                        let body: Body = summarize(self.program, i);
+                        // Any value but a multiple of 256 is non-zero in cells of every width.
+                        let runs: bool =
+                            certain && value.is_some_and(|value| value % 256i64 != 0i64);
+
+                        if body.movement == Some(0isize) {
+                            state.forget(&body.touched);
+                            self.block(i + 1usize, loop_end, &mut state.clone(), runs);
+                        } else {
+                            self.findings
+                                .push(Finding::UnbalancedLoop(i, body.movement));
+                            self.block(i + 1usize, loop_end, &mut State::unknown(), false);
+                            self.lose_track(state);
+                        }
+                        state.values.insert(state.pointer, Some(0i64));
+                    }
+                    i = loop_end;
+                }
+                Instruction::ProcedureStart => {
+                    let procedure_end: usize = self.program.jump_table[i];
+                    self.block(i + 1usize, procedure_end, &mut State::unknown(), false);
+                    i = procedure_end;
+                }
+                Instruction::Call => self.lose_track(state),
+                _ => (),
+            }
+            i += 1usize;
+        }
+    }
+}
+
+/// Analyses a program without running it, following the tape from the all-zero start.
+pub fn analyze(program: &Program) -> Analysis {
+    let mut analyzer: Analyzer = Analyzer {
+        program,
+        findings: Vec::new(),
+        max_pointer: 0isize,
+        tape_size_known: true,
+        underflowed: false,
+    };
+    analyzer.block(0usize, program.len(), &mut State::start(), true);
+    analyzer.findings.sort_by_key(Finding::instruction);
+
+    let loop_starts: Vec<usize> = (0..program.len())
+        .filter(|&i| program.instructions[i] == Instruction::LoopStart)
+        .collect();
+
+    Analysis {
+        findings: analyzer.findings,
+        loops: loop_starts.len(),
+        balanced_loops: loop_starts
+            .iter()
+            .filter(|&&start| summarize(program, start).movement == Some(0isize))
+            .count(),
+        tape_size: analyzer
+            .tape_size_known
+            .then_some(analyzer.max_pointer as usize + 1usize),
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt::Write as _;
+use std::fs::{read, read_dir};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use brainfuck::frontend::{self, Dialect, Program};
+use brainfuck::interpreters::{optimized, RuntimeError};
+use brainfuck::io::{Console, IoConfig};
+use brainfuck::ir::{self, Ir};
+use brainfuck::limits::Limits;
+use brainfuck::tape::{Cell, Tape, TapeConfig};
+
+/// How to run every program of a batch.
+pub struct BatchConfig {
+    pub dialect: Dialect,
+    pub tape_config: TapeConfig,
+    pub io_config: IoConfig,
+    pub limits: Limits,
+    /// Where `NAME.in` holds the input of `NAME.b`, if not next to the program. Programs without
+    /// one get no input.
+    pub input_directory: Option<PathBuf>,
+    pub jobs: usize,
+}
+
+/// What came of running one program with the optimized interpreter.
+pub struct Outcome {
+    pub path: PathBuf,
+    /// Why the program did not run to the end.
+    pub error: Option<String>,
+    /// FNV-1a hash of everything the program wrote, up to any error.
+    pub output_hash: u64,
+    /// Steps the optimized interpreter took, if the program ran to the end.
+    pub steps: Option<u64>,
+    pub time: Duration,
+}
+
+/// 64-bit FNV-1a, which is stable between runs and builds, unlike the standard library hashers.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3u64)
+    })
+}
+
+fn run_program<C: Cell>(path: &Path, config: &BatchConfig) -> Outcome {
+    let mut outcome: Outcome = Outcome {
+        path: path.to_path_buf(),
+        error: None,
+        output_hash: fnv1a(&[]),
+        steps: None,
+        time: Duration::ZERO,
+    };
+
+    let source: Vec<u8> = match read(path) {
+        Ok(source) => source,
+        Err(error) => {
+            outcome.error = Some(error.to_string());
+            return outcome;
+        }
+    };
+    let program: Program = match frontend::parse(&source, &config.dialect) {
+        Ok(program) => program,
+        Err(error) => {
+            outcome.error = Some(format!("{}: {}", error.location(), error));
+            return outcome;
+        }
+    };
+
+    let input_path: PathBuf = config
+        .input_directory
+        .as_deref()
+        .unwrap_or(path.parent().unwrap())
+        .join(path.with_extension("in").file_name().unwrap());
+    let input: Vec<u8> = read(input_path).unwrap_or_default();
+    let mut console: Console<&[u8], Vec<u8>> =
+        Console::with_io(input.as_slice(), Vec::new(), &config.io_config);
+    console.embed_input(&program.input);
+    let mut tape: Tape<C> = match Tape::new(&config.tape_config) {
+        Ok(tape) => tape,
+        Err(error) => {
+            outcome.error = Some(error.to_string());
+            return outcome;
+        }
+    };
+
+    let start: Instant = Instant::now();
+    let ir: Ir = ir::build_for_tape(&program, &config.tape_config);
+    let result: Result<u64, RuntimeError> = optimized::execute::<C, _, _, false>(
+        &ir,
+        &mut tape,
+        &mut console,
+        config.io_config.eof,
+        &config.limits,
+        &mut [],
+    );
+    outcome.time = start.elapsed();
+
+    match result {
+        Ok(steps) => outcome.steps = Some(steps),
+        Err(error) => outcome.error = Some(error.to_string()),
+    }
+    outcome.output_hash = fnv1a(&console.into_writer());
+    outcome
+}
+
+/// Runs every `.b` file in `directory` on `config.jobs` worker threads, each taking the next
+/// program once it is done with one. Outcomes are in order of file name.
+pub fn run<C: Cell>(directory: &Path, config: &BatchConfig) -> io::Result<Vec<Outcome>> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for entry in read_dir(directory)? {
+        let path: PathBuf = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|extension| extension == "b") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let next: AtomicUsize = AtomicUsize::new(0usize);
+    let outcomes: Mutex<Vec<Option<Outcome>>> = Mutex::new(paths.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..config.jobs.clamp(1usize, paths.len().max(1usize)) {
+            scope.spawn(|| loop {
+                let index: usize = next.fetch_add(1usize, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                let outcome: Outcome = run_program::<C>(path, config);
+                outcomes.lock().unwrap()[index] = Some(outcome);
+            });
+        }
+    });
+
+    Ok(outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(Option::unwrap)
+        .collect())
+}
+
+fn name(outcome: &Outcome) -> String {
+    outcome
+        .path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The outcomes as a table, one program per row.
+pub fn table(outcomes: &[Outcome]) -> String {
+    let width: usize = outcomes
+        .iter()
+        .map(|outcome| name(outcome).len())
+        .max()
+        .unwrap_or(0usize)
+        .max("program".len());
+    let mut table: String = String::new();
+
+    writeln!(
+        table,
+        "{:<width$} {:>16} {:>10} {:>16}  status",
+        "program", "steps", "time", "output hash"
+    )
+    .unwrap();
+    for outcome in outcomes {
+        writeln!(
+            table,
+            "{:<width$} {:>16} {:>9.3}s {:016x}  {}",
+            name(outcome),
+            outcome
+                .steps
+                .map_or("-".to_string(), |steps| steps.to_string()),
+            outcome.time.as_secs_f64(),
+            outcome.output_hash,
+            outcome.error.as_deref().unwrap_or("ok")
+        )
+        .unwrap();
+    }
+
+    let failed: usize = outcomes
+        .iter()
+        .filter(|outcome| outcome.error.is_some())
+        .count();
+    writeln!(
+        table,
+        "{} ran to the end, {} failed",
+        outcomes.len() - failed,
+        failed
+    )
+    .unwrap();
+
+    table
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped: String = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            character if character.is_control() => {
+                write!(escaped, "\\u{:04x}", character as u32).unwrap()
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// The outcomes as a JSON array of objects, with `error` null for programs that ran to the end.
+pub fn json(outcomes: &[Outcome]) -> String {
+    let objects: Vec<String> = outcomes
+        .iter()
+        .map(|outcome| {
+            format!(
+                "  {{\"program\": {}, \"error\": {}, \"steps\": {}, \"seconds\": {:.6}, \"output_hash\": \"{:016x}\"}}",
+                json_string(&outcome.path.to_string_lossy()),
+                outcome.error.as_deref().map_or("null".to_string(), json_string),
+                outcome
+                    .steps
+                    .map_or("null".to_string(), |steps| steps.to_string()),
+                outcome.time.as_secs_f64(),
+                outcome.output_hash
+            )
+        })
+        .collect();
+
+    if objects.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
@@ -0,0 +1,195 @@
+use std::fs::remove_dir_all;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use brainfuck::frontend::Program;
+use brainfuck::interpreters::{self, RuntimeError};
+use brainfuck::io::{Console, IoConfig};
+use brainfuck::ir::{self, Ir};
+use brainfuck::limits::Limits;
+use brainfuck::tape::{Bounds, Tape, TapeConfig};
+
+use crate::compilers::{self, CompileError};
+use crate::MEMORY_SIZE;
+
+/// The tape every backend runs on, since the JIT and the compiled binary only support this one.
+const TAPE_CONFIG: TapeConfig = TapeConfig {
+    length: MEMORY_SIZE,
+    growable: false,
+    max_length: usize::MAX,
+    bounds: Bounds::Error,
+};
+
+/// One run of the program through a backend.
+struct Measurement {
+    time: Duration,
+    /// Brainfuck instructions executed, which only the basic interpreter counts one by one.
+    instructions: Option<u64>,
+    output: Vec<u8>,
+}
+
+fn basic(program: &Program, io_config: &IoConfig, input: &[u8]) -> Result<Measurement, String> {
+    let mut tape: Tape<u8> = Tape::new(&TAPE_CONFIG).map_err(|error| error.to_string())?;
+    let mut console: Console<&[u8], Vec<u8>> = Console::with_io(input, Vec::new(), io_config);
+    console.embed_input(&program.input);
+
+    let start: Instant = Instant::now();
+    let instructions: u64 = interpreters::basic::execute(
+        program,
+        &mut tape,
+        &mut console,
+        io_config.eof,
+        &Limits::default(),
+    )
+    .map_err(|error: RuntimeError| error.to_string())?;
+
+    Ok(Measurement {
+        time: start.elapsed(),
+        instructions: Some(instructions),
+        output: console.into_writer(),
+    })
+}
+
+fn optimized(program: &Program, io_config: &IoConfig, input: &[u8]) -> Result<Measurement, String> {
+    let mut tape: Tape<u8> = Tape::new(&TAPE_CONFIG).map_err(|error| error.to_string())?;
+    let mut console: Console<&[u8], Vec<u8>> = Console::with_io(input, Vec::new(), io_config);
+    console.embed_input(&program.input);
+
+    let start: Instant = Instant::now();
+    // Building the IR is part of what the optimized interpreter costs.
+    let ir: Ir = ir::build(program);
+    interpreters::optimized::execute::<u8, _, _, false>(
+        &ir,
+        &mut tape,
+        &mut console,
+        io_config.eof,
+        &Limits::default(),
+        &mut [],
+    )
+    .map_err(|error: RuntimeError| error.to_string())?;
+
+    Ok(Measurement {
+        time: start.elapsed(),
+        instructions: None,
+        output: console.into_writer(),
+    })
+}
+
+#[cfg(all(target_arch = "x86_64", unix))]
+fn jit(program: &Program, io_config: &IoConfig, input: &[u8]) -> Result<Measurement, String> {
+    let mut console: Console<&[u8], Vec<u8>> = Console::with_io(input, Vec::new(), io_config);
+    console.embed_input(&program.input);
+
+    let start: Instant = Instant::now();
+    interpreters::jit::execute(program, &TAPE_CONFIG, &mut console, io_config.eof)
+        .map_err(|error: RuntimeError| error.to_string())?;
+
+    Ok(Measurement {
+        time: start.elapsed(),
+        instructions: None,
+        output: console.into_writer(),
+    })
+}
+
+/// Builds the program with `compilers::optimized` and times the binary, leaving the time spent
+/// in rustc out.
+fn compiled(program: &Program, io_config: &IoConfig, input: &[u8]) -> Result<Measurement, String> {
+    let directory: PathBuf =
+        compilers::optimized::scratch_directory().map_err(|error| error.to_string())?;
+    let executable: PathBuf = directory.join("bench");
+    let result: Result<Measurement, String> =
+        compilers::optimized::compile(program, &executable.to_string_lossy(), io_config, None)
+            .map_err(|error: CompileError| error.to_string())
+            .and_then(|_| run_executable(&executable, input).map_err(|error| error.to_string()));
+    remove_dir_all(directory).map_err(|error| error.to_string())?;
+
+    result
+}
+
+fn run_executable(executable: &Path, input: &[u8]) -> std::io::Result<Measurement> {
+    let start: Instant = Instant::now();
+    let mut child: Child = Command::new(executable)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    // A program that stops reading early closes its stdin, which is no reason to fail.
+    let _ = child.stdin.take().unwrap().write_all(input);
+    let output: Output = child.wait_with_output()?;
+    let time: Duration = start.elapsed();
+
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!(
+            "binary exited with {}",
+            output.status
+        )));
+    }
+    Ok(Measurement {
+        time,
+        instructions: None,
+        output: output.stdout,
+    })
+}
+
+/// Runs the program through every backend with the same input, and prints the time each took and
+/// how much faster it was than the basic interpreter as soon as it finishes, or "n/a" if the basic
+/// interpreter failed. Output from the program is compared between backends instead of printed.
+pub fn run(program: &Program, io_config: &IoConfig, input: &[u8]) {
+    type Backend = fn(&Program, &IoConfig, &[u8]) -> Result<Measurement, String>;
+    let backends: Vec<(&str, Backend)> = vec![
+        ("basic", basic),
+        ("optimized", optimized),
+        #[cfg(all(target_arch = "x86_64", unix))]
+        ("jit", jit),
+        ("compiled", compiled),
+    ];
+
+    println!(
+        "{:<10} {:>12} {:>16} {:>9}",
+        "backend", "time", "instructions", "speedup"
+    );
+
+    let mut basic_time: Option<Duration> = None;
+    // The output of the first backend to succeed, which the others are compared with.
+    let mut reference: Option<(&str, Vec<u8>)> = None;
+    for (name, backend) in backends {
+        let measurement: Measurement = match backend(program, io_config, input) {
+            Ok(measurement) => measurement,
+            Err(error) => {
+                println!("{:<10} failed: {}", name, error);
+                continue;
+            }
+        };
+        if name == "basic" {
+            basic_time = Some(measurement.time);
+        }
+
+        let speedup: String = basic_time.map_or("n/a".to_string(), |basic_time| {
+            format!(
+                "{:.2}x",
+                basic_time.as_secs_f64() / measurement.time.as_secs_f64().max(1e-9f64)
+            )
+        });
+        println!(
+            "{:<10} {:>11.3}s {:>16} {:>9}",
+            name,
+            measurement.time.as_secs_f64(),
+            measurement
+                .instructions
+                .map_or("-".to_string(), |instructions| instructions.to_string()),
+            speedup
+        );
+
+        match &reference {
+            Some((reference_name, output)) if *output != measurement.output => {
+                println!(
+                    "  warning: output differs from the {} backend",
+                    reference_name
+                )
+            }
+            Some(_) => {}
+            None => reference = Some((name, measurement.output)),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::Write as _;
+
+use brainfuck::io::{Eof, IoConfig, IoMode};
+use brainfuck::ir::Op;
+
+use crate::compilers::Target;
+use crate::MEMORY_SIZE;
+
+const FLUSH_STDOUT: &str =
+    "    movq stdout@GOTPCREL(%rip), %rax\n    movq (%rax), %rdi\n    call fflush@PLT\n";
+
+/// x86-64 GNU assembler (AT&T syntax) for the System V ABI, linking against libc for I/O, to be
+/// built with `cc`.
+///
+/// The memory pointer lives in the callee-saved `rbx`, so it survives the libc calls.
+pub struct Asm;
+
+impl Target for Asm {
+    const CURRENT: &'static str = "(%rbx)";
+
+    fn indexed(offset: isize) -> String {
+        format!("{}(%rbx)", offset)
+    }
+
+    fn transpile(ops: &[Op], io_config: &IoConfig) -> String {
+        let mut source: String = String::new();
+        // Label numbers of the loops currently open.
+        let mut loop_labels: Vec<usize> = Vec::new();
+        let mut label_count: usize = 0usize;
+
+        writeln!(source, "    .text").unwrap();
+        writeln!(source, "    .globl main").unwrap();
+        writeln!(source, "main:").unwrap();
+        writeln!(source, "    pushq %rbx").unwrap();
+        writeln!(source, "    leaq memory(%rip), %rbx").unwrap();
+
+        for op in ops {
+            match *op {
+                Op::Add(amount) => writeln!(source, "    addb ${}, (%rbx)", amount as u8).unwrap(),
+                Op::Move(distance) if distance < 0 => {
+                    writeln!(source, "    subq ${}, %rbx", distance.unsigned_abs()).unwrap()
+                }
+                Op::Move(distance) => writeln!(source, "    addq ${}, %rbx", distance).unwrap(),
+                Op::SetZero => writeln!(source, "    movb $0, (%rbx)").unwrap(),
+                Op::MulAdd { offset, factor } => {
+                    let label: usize = label_count;
+                    label_count += 1usize;
+                    writeln!(
+                        source,
+                        "    # if (p[0]) p[{}] += p[0] * {}",
+                        offset, factor as u8
+                    )
+                    .unwrap();
+                    writeln!(source, "    movzbl (%rbx), %eax").unwrap();
+                    writeln!(source, "    testl %eax, %eax").unwrap();
+                    writeln!(source, "    jz .Lmul{}_end", label).unwrap();
+                    writeln!(source, "    imull ${}, %eax, %eax", factor as u8).unwrap();
+                    writeln!(source, "    addb %al, {}", Self::cell(offset)).unwrap();
+                    writeln!(source, ".Lmul{}_end:", label).unwrap();
+                }
+                Op::ScanLeft(stride) | Op::ScanRight(stride) => {
+                    let label: usize = label_count;
+                    label_count += 1usize;
+                    let instruction: &str = if let Op::ScanLeft(_) = op {
+                        "subq"
+                    } else {
+                        "addq"
+                    };
+                    writeln!(source, ".Lscan{}:", label).unwrap();
+                    writeln!(source, "    cmpb $0, (%rbx)").unwrap();
+                    writeln!(source, "    je .Lscan{}_end", label).unwrap();
+                    writeln!(source, "    {} ${}, %rbx", instruction, stride).unwrap();
+                    writeln!(source, "    jmp .Lscan{}", label).unwrap();
+                    writeln!(source, ".Lscan{}_end:", label).unwrap();
+                }
+                Op::Output => {
+                    let label: usize = label_count;
+                    label_count += 1usize;
+                    writeln!(source, "    movzbl (%rbx), %edi").unwrap();
+                    if io_config.mode == IoMode::Text {
+                        writeln!(source, "    cmpl $127, %edi").unwrap();
+                        writeln!(source, "    ja .Loutput{}_end", label).unwrap();
+                    }
+                    writeln!(source, "    call putchar@PLT").unwrap();
+                    if io_config.mode == IoMode::Text {
+                        source.push_str(FLUSH_STDOUT);
+                        writeln!(source, ".Loutput{}_end:", label).unwrap();
+                    }
+                }
+                Op::Input => {
+                    let label: usize = label_count;
+                    label_count += 1usize;
+                    if io_config.mode == IoMode::Raw {
+                        source.push_str(FLUSH_STDOUT);
+                    }
+                    writeln!(source, "    call getchar@PLT").unwrap();
+                    writeln!(source, "    cmpl $-1, %eax").unwrap();
+                    writeln!(source, "    je .Linput{}_eof", label).unwrap();
+                    writeln!(source, "    movb %al, (%rbx)").unwrap();
+                    writeln!(source, "    jmp .Linput{}_end", label).unwrap();
+                    writeln!(source, ".Linput{}_eof:", label).unwrap();
+                    match io_config.eof {
+                        Eof::Unchanged => {}
+                        Eof::Zero => writeln!(source, "    movb $0, (%rbx)").unwrap(),
+                        Eof::MinusOne => writeln!(source, "    movb $255, (%rbx)").unwrap(),
+                    }
+                    writeln!(source, ".Linput{}_end:", label).unwrap();
+                }
+                Op::LoopStart(_) => {
+                    let label: usize = label_count;
+                    label_count += 1usize;
+                    loop_labels.push(label);
+                    writeln!(source, "    cmpb $0, (%rbx)").unwrap();
+                    writeln!(source, "    je .Lloop{}_end", label).unwrap();
+                    writeln!(source, ".Lloop{}:", label).unwrap();
+                }
+                Op::LoopEnd(_) => {
+                    let label: usize = loop_labels.pop().unwrap();
+                    writeln!(source, "    cmpb $0, (%rbx)").unwrap();
+                    writeln!(source, "    jne .Lloop{}", label).unwrap();
+                    writeln!(source, ".Lloop{}_end:", label).unwrap();
+                }
+                op => unreachable!("{:?} is rejected before transpiling", op),
+            }
+        }
+
+        writeln!(source, "    xorl %eax, %eax").unwrap();
+        writeln!(source, "    popq %rbx").unwrap();
+        writeln!(source, "    ret\n").unwrap();
+        writeln!(source, "    .lcomm memory, {}", MEMORY_SIZE).unwrap();
+        writeln!(source, "    .section .note.GNU-stack,\"\",@progbits").unwrap();
+
+        source
+    }
+}
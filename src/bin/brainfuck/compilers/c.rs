@@ -0,0 +1,98 @@
+use std::fmt::Write as _;
+
+use brainfuck::io::{Eof, IoConfig, IoMode};
+use brainfuck::ir::Op;
+
+use crate::compilers::Target;
+use crate::MEMORY_SIZE;
+
+/// C, to be built with any C compiler.
+pub struct C;
+
+/// Splits an amount into a compound assignment operator and the 8-bit magnitude closest to zero.
+fn signed(amount: i32) -> (&'static str, u8) {
+    let amount: i8 = amount as u8 as i8;
+    if amount < 0 {
+        ("-=", amount.unsigned_abs())
+    } else {
+        ("+=", amount as u8)
+    }
+}
+
+impl Target for C {
+    const CURRENT: &'static str = "*p";
+
+    fn indexed(offset: isize) -> String {
+        format!("p[{}]", offset)
+    }
+
+    fn transpile(ops: &[Op], io_config: &IoConfig) -> String {
+        let mut source: String = String::new();
+        let mut depth: usize = 1usize;
+
+        writeln!(source, "#include <stdio.h>\n").unwrap();
+        writeln!(source, "#define MEMORY_SIZE {}\n", MEMORY_SIZE).unwrap();
+        writeln!(source, "static unsigned char memory[MEMORY_SIZE];\n").unwrap();
+        writeln!(source, "int main(void) {{").unwrap();
+        writeln!(source, "    unsigned char *p = memory;").unwrap();
+        if ops.contains(&Op::Input) {
+            writeln!(source, "    int c;").unwrap();
+        }
+        writeln!(source).unwrap();
+
+        for op in ops {
+            if let Op::LoopEnd(_) = op {
+                depth -= 1usize;
+            }
+            let indentation: String = "    ".repeat(depth);
+
+            let statement: String = match *op {
+                Op::Add(amount) => {
+                    let (operator, value): (&str, u8) = signed(amount);
+                    format!("*p {} {};", operator, value)
+                }
+                Op::Move(distance) if distance < 0 => format!("p -= {};", distance.unsigned_abs()),
+                Op::Move(distance) => format!("p += {};", distance),
+                Op::SetZero => "*p = 0;".to_string(),
+                Op::MulAdd { offset, factor } => match signed(factor) {
+                    (operator, 1) => format!("if (*p) {} {} *p;", Self::cell(offset), operator),
+                    (operator, value) => {
+                        format!(
+                            "if (*p) {} {} *p * {};",
+                            Self::cell(offset),
+                            operator,
+                            value
+                        )
+                    }
+                },
+                Op::ScanLeft(stride) => format!("while (*p) p -= {};", stride),
+                Op::ScanRight(stride) => format!("while (*p) p += {};", stride),
+                Op::Output if io_config.mode == IoMode::Raw => "putchar(*p);".to_string(),
+                Op::Output => "if (*p < 128) { putchar(*p); fflush(stdout); }".to_string(),
+                Op::Input => {
+                    let read: &str = match io_config.mode {
+                        IoMode::Raw => "fflush(stdout); c = getchar();",
+                        IoMode::Text => "c = getchar();",
+                    };
+                    match io_config.eof {
+                        Eof::Unchanged => format!("{} if (c != EOF) *p = c;", read),
+                        Eof::Zero => format!("{} *p = c == EOF ? 0 : c;", read),
+                        Eof::MinusOne => format!("{} *p = c;", read),
+                    }
+                }
+                Op::LoopStart(_) => "while (*p) {".to_string(),
+                Op::LoopEnd(_) => "}".to_string(),
+                op => unreachable!("{:?} is rejected before transpiling", op),
+            };
+            writeln!(source, "{}{}", indentation, statement).unwrap();
+
+            if let Op::LoopStart(_) = op {
+                depth += 1usize;
+            }
+        }
+
+        writeln!(source, "\n    return 0;\n}}").unwrap();
+
+        source
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, Write};
+use std::process::ExitStatus;
+
+use brainfuck::frontend::Program;
+use brainfuck::io::IoConfig;
+use brainfuck::ir::{self, Ir, Op};
+
+const RUST_INTERMEDIARY_FILENAME: &str = "transpiled_brainfuck.rs";
+
+pub mod asm;
+pub mod c;
+pub mod optimized;
+pub mod wat;
+
+#[derive(Debug)]
+pub enum CompileError {
+    Io(io::Error),
+    Unsupported(&'static str),
+    /// `rustc` ran but rejected the transpiled program.
+    Rustc {
+        status: ExitStatus,
+        stderr: String,
+    },
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Io(error) => write!(f, "{}", error),
+            CompileError::Unsupported(reason) => write!(f, "{}", reason),
+            CompileError::Rustc { status, stderr } => {
+                write!(f, "rustc failed ({})\n{}", status, stderr.trim_end())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// Builds the IR of a program, which must be standard brainfuck.
+fn standard_ir(program: &Program) -> Result<Ir, CompileError> {
+    let ir: Ir = ir::build(program);
+    if ir.ops.iter().any(Op::is_extension) || !program.input.is_empty() {
+        return Err(CompileError::Unsupported(
+            "the compiler does not support dialect extensions",
+        ));
+    }
+    Ok(ir)
+}
+
+impl From<io::Error> for CompileError {
+    fn from(error: io::Error) -> CompileError {
+        CompileError::Io(error)
+    }
+}
+
+/// A language that standard programs are translated to as source text.
+///
+/// Every target guards a `MulAdd` with the cell under the pointer, as the loop it was folded from
+/// never touches its other cells when that cell is zero. Unguarded, a multiplication by zero
+/// could still reach past either end of the tape.
+pub trait Target {
+    /// The cell under the pointer.
+    const CURRENT: &'static str;
+
+    /// The cell `offset` cells from the pointer, written as an index even when `offset` is 0.
+    fn indexed(offset: isize) -> String;
+
+    /// Translates the ops of a standard program into a complete source file.
+    fn transpile(ops: &[Op], io_config: &IoConfig) -> String;
+
+    /// The cell `offset` cells from the pointer, in its shortest form.
+    fn cell(offset: isize) -> String {
+        match offset {
+            0 => Self::CURRENT.to_string(),
+            _ => Self::indexed(offset),
+        }
+    }
+}
+
+/// Writes the program to `output_path` as a source file of the target `T`.
+pub fn compile<T: Target>(
+    program: &Program,
+    output_path: &str,
+    io_config: &IoConfig,
+) -> Result<(), CompileError> {
+    File::create(output_path)?
+        .write_all(T::transpile(&standard_ir(program)?.ops, io_config).as_bytes())?;
+    Ok(())
+}
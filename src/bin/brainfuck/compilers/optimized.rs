@@ -0,0 +1,194 @@
+use std::env::temp_dir;
+use std::fmt::Write as _;
+use std::fs::{create_dir, remove_dir_all, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use brainfuck::frontend::Program;
+use brainfuck::io::{Eof, IoConfig, IoMode};
+use brainfuck::ir::{Ir, Op};
+
+use crate::compilers::{standard_ir, CompileError, RUST_INTERMEDIARY_FILENAME};
+use crate::MEMORY_SIZE;
+
+fn offset_index(offset: isize) -> String {
+    if offset < 0 {
+        format!("memory_pointer - {}usize", offset.unsigned_abs())
+    } else {
+        format!("memory_pointer + {}usize", offset)
+    }
+}
+
+/// Transpiles the IR into an indented Rust program, with comments mapping every loop, and every
+/// loop folded into straight-line code, back to its offset in the brainfuck source.
+fn transpile(ir: &Ir, io_config: &IoConfig) -> Vec<u8> {
+    let mut transpiled_program: String = String::new();
+    let mut depth: usize = 1usize;
+    let has_input: bool = ir.ops.contains(&Op::Input);
+    let eof_statement: &str = match io_config.eof {
+        Eof::Unchanged => "",
+        Eof::Zero => "memory[memory_pointer] = 0u8;",
+        Eof::MinusOne => "memory[memory_pointer] = 255u8;",
+    };
+
+    if has_input {
+        writeln!(
+            transpiled_program,
+            "use std::io::{{stdin, stdout, BufRead, BufWriter, StdinLock, StdoutLock, Write}};\n"
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            transpiled_program,
+            "use std::io::{{stdout, BufWriter, StdoutLock, Write}};\n"
+        )
+        .unwrap();
+    }
+    writeln!(
+        transpiled_program,
+        "const MEMORY_SIZE: usize = {}usize;\n",
+        MEMORY_SIZE
+    )
+    .unwrap();
+    writeln!(transpiled_program, "fn main() {{").unwrap();
+    writeln!(transpiled_program, "    let mut buffered_stdout_lock: BufWriter<StdoutLock> = BufWriter::new(stdout().lock());").unwrap();
+    if has_input {
+        writeln!(
+            transpiled_program,
+            "    let mut stdin_lock: StdinLock = stdin().lock();"
+        )
+        .unwrap();
+        if io_config.mode == IoMode::Text {
+            writeln!(
+                transpiled_program,
+                "    let mut input_buffer: Vec<u8> = Vec::new();"
+            )
+            .unwrap();
+        }
+    }
+    writeln!(
+        transpiled_program,
+        "    let mut memory: [u8; MEMORY_SIZE] = [0u8; MEMORY_SIZE];"
+    )
+    .unwrap();
+    writeln!(
+        transpiled_program,
+        "    let mut memory_pointer: usize = 0usize;"
+    )
+    .unwrap();
+
+    for (i, op) in ir.ops.iter().enumerate() {
+        if let Op::LoopEnd(_) = op {
+            depth -= 1usize;
+        }
+        let indentation: String = "    ".repeat(depth);
+
+        let starts_folded_loop: bool = matches!(
+            op,
+            Op::SetZero | Op::MulAdd { .. } | Op::ScanLeft(_) | Op::ScanRight(_)
+        ) && (i == 0usize
+            || ir.offsets[i - 1usize] != ir.offsets[i]);
+        if starts_folded_loop {
+            writeln!(
+                transpiled_program,
+                "\n{}// Folded loop at source offset {}",
+                indentation, ir.offsets[i]
+            )
+            .unwrap();
+        } else if let Op::LoopStart(_) = op {
+            writeln!(
+                transpiled_program,
+                "\n{}// Loop at source offset {}",
+                indentation, ir.offsets[i]
+            )
+            .unwrap();
+        }
+
+        let statement: String = match *op {
+            Op::Add(amount) => format!("memory[memory_pointer] = memory[memory_pointer].wrapping_add({}u8);", amount as u8),
+            Op::Move(distance) if distance < 0 => format!("memory_pointer -= {}usize;", distance.unsigned_abs()),
+            Op::Move(distance) => format!("memory_pointer += {}usize;", distance),
+            Op::SetZero => "memory[memory_pointer] = 0u8;".to_string(),
+            Op::MulAdd { offset, factor } => format!("if memory[memory_pointer] != 0u8 {{\n{2}    memory[{0}] = memory[{0}].wrapping_add(memory[memory_pointer].wrapping_mul({1}u8));\n{2}}}", offset_index(offset), factor as u8, indentation),
+            Op::ScanLeft(stride) => format!("while memory[memory_pointer] != 0u8 {{\n{1}    memory_pointer -= {0}usize;\n{1}}}", stride, indentation),
+            Op::ScanRight(stride) => format!("while memory[memory_pointer] != 0u8 {{\n{1}    memory_pointer += {0}usize;\n{1}}}", stride, indentation),
+            Op::Output if io_config.mode == IoMode::Raw => "buffered_stdout_lock.write_all(&[memory[memory_pointer]]).unwrap();".to_string(),
+            Op::Output => format!("if memory[memory_pointer].is_ascii() {{\n{0}    buffered_stdout_lock.write_all(&[memory[memory_pointer]]).unwrap();\n{0}    buffered_stdout_lock.flush().unwrap();\n{0}}}", indentation),
+            Op::Input if io_config.mode == IoMode::Raw => format!("buffered_stdout_lock.flush().unwrap();\n{1}match stdin_lock.fill_buf().unwrap().first().copied() {{\n{1}    Some(byte) => {{\n{1}        memory[memory_pointer] = byte;\n{1}        stdin_lock.consume(1usize);\n{1}    }}\n{1}    None => {{{0}}}\n{1}}}", eof_statement, indentation),
+            Op::Input if io_config.eof == Eof::Unchanged => format!("if input_buffer.is_empty() {{\n{0}    stdin_lock.read_until(b'\\n', &mut input_buffer).unwrap();\n{0}}}\n{0}if !input_buffer.is_empty() {{\n{0}    memory[memory_pointer] = input_buffer.remove(0usize);\n{0}}}", indentation),
+            Op::Input => format!("if input_buffer.is_empty() {{\n{1}    stdin_lock.read_until(b'\\n', &mut input_buffer).unwrap();\n{1}}}\n{1}if input_buffer.is_empty() {{\n{1}    {0}\n{1}}} else {{\n{1}    memory[memory_pointer] = input_buffer.remove(0usize);\n{1}}}", eof_statement, indentation),
+            Op::LoopStart(_) => "while memory[memory_pointer] != 0u8 {".to_string(),
+            Op::LoopEnd(_) => "}".to_string(),
+            op => unreachable!("{:?} is rejected before transpiling", op),
+        };
+        writeln!(transpiled_program, "{}{}", indentation, statement).unwrap();
+
+        if let Op::LoopStart(_) = op {
+            depth += 1usize;
+        }
+    }
+
+    writeln!(
+        transpiled_program,
+        "\n    buffered_stdout_lock.flush().unwrap();\n}}"
+    )
+    .unwrap();
+
+    transpiled_program.into_bytes()
+}
+
+/// A fresh directory under the system temp directory, so that parallel builds never share an
+/// intermediary file.
+pub fn scratch_directory() -> Result<PathBuf, CompileError> {
+    let nanos: u128 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0u128, |duration| duration.as_nanos());
+    let path_buf: PathBuf = temp_dir().join(format!("brainfuck-{}-{}", process::id(), nanos));
+    create_dir(&path_buf)?;
+    Ok(path_buf)
+}
+
+fn rustc(source_path: &Path, output_path: &str) -> Result<(), CompileError> {
+    let output: Output = Command::new("rustc")
+        .arg(source_path)
+        .args(["-o", output_path, "-C", "opt-level=3", "-C", "debuginfo=0"])
+        .output()
+        .map_err(|error| io::Error::new(error.kind(), format!("could not run rustc: {}", error)))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(CompileError::Rustc {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+/// Compiles the program with `rustc`. The transpiled source is written to `source_path` and kept
+/// if given, and otherwise written to a scratch directory that is removed afterwards.
+pub fn compile(
+    program: &Program,
+    output_path: &str,
+    io_config: &IoConfig,
+    source_path: Option<&String>,
+) -> Result<(), CompileError> {
+    let transpiled_program: Vec<u8> = transpile(&standard_ir(program)?, io_config);
+
+    if let Some(source_path) = source_path {
+        File::create(source_path)?.write_all(&transpiled_program)?;
+        return rustc(Path::new(source_path), output_path);
+    }
+
+    let directory: PathBuf = scratch_directory()?;
+    let intermediary_path: PathBuf = directory.join(RUST_INTERMEDIARY_FILENAME);
+    let result: Result<(), CompileError> = File::create(&intermediary_path)
+        .and_then(|mut file| file.write_all(&transpiled_program))
+        .map_err(CompileError::from)
+        .and_then(|_| rustc(&intermediary_path, output_path));
+    remove_dir_all(directory)?;
+
+    result
+}
@@ -0,0 +1,246 @@
+use std::fmt::Write as _;
+
+use brainfuck::io::{Eof, IoConfig, IoMode};
+use brainfuck::ir::Op;
+
+use crate::compilers::Target;
+use crate::MEMORY_SIZE;
+
+/// The tape starts at address 0, so that a cell left of it wraps around to an address as far from
+/// the tape as any right of it, and a single unsigned comparison rejects both.
+const TAPE: usize = 0usize;
+/// The `iovec` handed to `fd_read` and `fd_write`, a pointer followed by a length.
+const IOVEC: usize = TAPE + MEMORY_SIZE;
+/// Where WASI stores the number of bytes read or written.
+const RESULT: usize = IOVEC + 8usize;
+const INPUT_BYTE: usize = IOVEC + 12usize;
+const OUTPUT_BUFFER: usize = IOVEC + 16usize;
+const OUTPUT_BUFFER_SIZE: usize = 4096usize;
+const PAGE_SIZE: usize = 65536usize;
+
+/// The value of the cell under the pointer.
+const CURRENT_VALUE: &str = "(i32.load8_u (local.get $p))";
+
+/// Writes out the output buffer, retrying partial writes until it is empty, and traps if a write
+/// fails rather than lose the output.
+const FLUSH: &str = "  (func $flush
+    (local $written i32)
+    (block $done
+      (loop $write
+        (br_if $done (i32.eqz (global.get $buffered)))
+        (i32.store (i32.const IOVEC) (i32.add (i32.const OUTPUT_BUFFER) (local.get $written)))
+        (i32.store (i32.const IOVEC_LENGTH) (global.get $buffered))
+        (if (call $fd_write (i32.const 1) (i32.const IOVEC) (i32.const 1) (i32.const RESULT))
+          (then (unreachable)))
+        (local.set $written (i32.add (local.get $written) (i32.load (i32.const RESULT))))
+        (global.set $buffered (i32.sub (global.get $buffered) (i32.load (i32.const RESULT))))
+        (br $write)))
+    (global.set $buffered (i32.const 0)))
+";
+
+/// Reads one byte from stdin, or returns -1 at EOF or on an error.
+const INPUT: &str = "  (func $input (result i32)
+    (i32.store (i32.const IOVEC) (i32.const INPUT_BYTE))
+    (i32.store (i32.const IOVEC_LENGTH) (i32.const 1))
+    (if (call $fd_read (i32.const 0) (i32.const IOVEC) (i32.const 1) (i32.const RESULT))
+      (then (return (i32.const -1))))
+    (if (i32.eqz (i32.load (i32.const RESULT)))
+      (then (return (i32.const -1))))
+    (i32.load8_u (i32.const INPUT_BYTE)))
+";
+
+/// Fills in the memory layout constants of a helper function.
+fn helper(template: &str) -> String {
+    template
+        .replace("IOVEC_LENGTH", &(IOVEC + 4usize).to_string())
+        .replace("IOVEC", &IOVEC.to_string())
+        .replace("RESULT", &RESULT.to_string())
+        .replace("INPUT_BYTE", &INPUT_BYTE.to_string())
+        .replace("OUTPUT_BUFFER", &OUTPUT_BUFFER.to_string())
+}
+
+/// Traps unless `address` is on the tape.
+fn check_bounds(address: &str) -> String {
+    format!(
+        "(if (i32.ge_u {} (i32.const {})) (then (unreachable)))",
+        address,
+        TAPE + MEMORY_SIZE
+    )
+}
+
+/// A WebAssembly text module for WASI, to be run with any WASI runtime or assembled with
+/// `wat2wasm`.
+pub struct Wat;
+
+impl Target for Wat {
+    const CURRENT: &'static str = "(local.get $p)";
+
+    fn indexed(offset: isize) -> String {
+        format!("(i32.add (local.get $p) (i32.const {}))", offset)
+    }
+
+    fn transpile(ops: &[Op], io_config: &IoConfig) -> String {
+        let has_input: bool = ops.contains(&Op::Input);
+        let has_output: bool = ops.contains(&Op::Output);
+        let mut source: String = String::new();
+        // Label numbers of the loops currently open.
+        let mut loop_labels: Vec<usize> = Vec::new();
+        let mut label_count: usize = 0usize;
+
+        writeln!(source, "(module").unwrap();
+        if has_input {
+            writeln!(
+            source,
+            "  (import \"wasi_snapshot_preview1\" \"fd_read\" (func $fd_read (param i32 i32 i32 i32) (result i32)))"
+        )
+        .unwrap();
+        }
+        if has_output {
+            writeln!(
+            source,
+            "  (import \"wasi_snapshot_preview1\" \"fd_write\" (func $fd_write (param i32 i32 i32 i32) (result i32)))"
+        )
+        .unwrap();
+        }
+        writeln!(
+            source,
+            "\n  (memory (export \"memory\") {})",
+            (OUTPUT_BUFFER + OUTPUT_BUFFER_SIZE).div_ceil(PAGE_SIZE)
+        )
+        .unwrap();
+
+        if has_output {
+            writeln!(source, "  (global $buffered (mut i32) (i32.const 0))\n").unwrap();
+            source.push_str(&helper(FLUSH));
+            writeln!(source, "\n  (func $output (param $byte i32)").unwrap();
+            if io_config.mode == IoMode::Text {
+                writeln!(
+                    source,
+                    "    (if (i32.ge_u (local.get $byte) (i32.const 128)) (then (return)))"
+                )
+                .unwrap();
+            }
+            writeln!(
+            source,
+            "    (i32.store8 (i32.add (i32.const {}) (global.get $buffered)) (local.get $byte))",
+            OUTPUT_BUFFER
+        )
+            .unwrap();
+            writeln!(
+                source,
+                "    (global.set $buffered (i32.add (global.get $buffered) (i32.const 1)))"
+            )
+            .unwrap();
+            match io_config.mode {
+            IoMode::Text => writeln!(source, "    (call $flush))").unwrap(),
+            IoMode::Raw => writeln!(
+                source,
+                "    (if (i32.eq (global.get $buffered) (i32.const {}))\n      (then (call $flush))))",
+                OUTPUT_BUFFER_SIZE
+            )
+            .unwrap(),
+        }
+        }
+        if has_input {
+            writeln!(source).unwrap();
+            source.push_str(&helper(INPUT));
+        }
+
+        writeln!(source, "\n  (func $main (export \"_start\")").unwrap();
+        writeln!(source, "    (local $p i32)").unwrap();
+        if has_input {
+            writeln!(source, "    (local $c i32)").unwrap();
+        }
+        writeln!(source, "    (local.set $p (i32.const {}))", TAPE).unwrap();
+
+        for op in ops {
+            if let Op::LoopEnd(_) = op {
+                let label: usize = loop_labels.pop().unwrap();
+                let indentation: String = "    ".repeat(loop_labels.len() + 1usize);
+                writeln!(
+                    source,
+                    "{}    (br_if $loop{} {}))",
+                    indentation, label, CURRENT_VALUE
+                )
+                .unwrap();
+                writeln!(source, "{})", indentation).unwrap();
+                continue;
+            }
+            let indentation: String = "    ".repeat(loop_labels.len() + 1usize);
+
+            let statement: String = match *op {
+            Op::Add(amount) => format!(
+                "(i32.store8 (local.get $p) (i32.add {} (i32.const {})))",
+                CURRENT_VALUE, amount as u8
+            ),
+            Op::Move(distance) => format!(
+                "(local.set $p (i32.add (local.get $p) (i32.const {})))\n{}{}",
+                distance,
+                indentation,
+                check_bounds("(local.get $p)")
+            ),
+            Op::SetZero => "(i32.store8 (local.get $p) (i32.const 0))".to_string(),
+            Op::MulAdd { offset, factor } => format!(
+                "(if {}\n{indentation}  (then\n{indentation}    {}\n{indentation}    (i32.store8 {} (i32.add (i32.load8_u {}) (i32.mul {} (i32.const {}))))))",
+                CURRENT_VALUE,
+                check_bounds(&Self::cell(offset)),
+                Self::cell(offset),
+                Self::cell(offset),
+                CURRENT_VALUE,
+                factor as u8
+            ),
+            Op::ScanLeft(stride) | Op::ScanRight(stride) => {
+                let label: usize = label_count;
+                label_count += 1usize;
+                let distance: isize = match op {
+                    Op::ScanLeft(_) => -(stride as isize),
+                    _ => stride as isize,
+                };
+                format!(
+                    "(block $scan{label}_end\n{indentation}  (loop $scan{label}\n{indentation}    (br_if $scan{label}_end (i32.eqz {}))\n{indentation}    (local.set $p (i32.add (local.get $p) (i32.const {})))\n{indentation}    {}\n{indentation}    (br $scan{label})))",
+                    CURRENT_VALUE,
+                    distance,
+                    check_bounds("(local.get $p)")
+                )
+            }
+            Op::Output => format!("(call $output {})", CURRENT_VALUE),
+            Op::Input => {
+                let read: String = match io_config.mode {
+                    IoMode::Raw if has_output => "(call $flush)\n".to_string() + &indentation,
+                    _ => String::new(),
+                };
+                let store: &str = match io_config.eof {
+                    Eof::Unchanged => "(if (i32.ge_s (local.get $c) (i32.const 0))\n  (then (i32.store8 (local.get $p) (local.get $c))))",
+                    Eof::Zero => "(i32.store8 (local.get $p) (select (local.get $c) (i32.const 0) (i32.ge_s (local.get $c) (i32.const 0))))",
+                    // -1 truncates to 255.
+                    Eof::MinusOne => "(i32.store8 (local.get $p) (local.get $c))",
+                };
+                format!(
+                    "{}(local.set $c (call $input))\n{}{}",
+                    read,
+                    indentation,
+                    store.replace("\n", &format!("\n{}", indentation))
+                )
+            }
+            Op::LoopStart(_) => {
+                let label: usize = label_count;
+                label_count += 1usize;
+                loop_labels.push(label);
+                format!(
+                    "(block $loop{label}_end\n{indentation}  (br_if $loop{label}_end (i32.eqz {}))\n{indentation}  (loop $loop{label}",
+                    CURRENT_VALUE
+                )
+            }
+            op => unreachable!("{:?} is rejected before transpiling", op),
+        };
+            writeln!(source, "{}{}", indentation, statement).unwrap();
+        }
+
+        if has_output {
+            writeln!(source, "    (call $flush)").unwrap();
+        }
+        writeln!(source, "  )\n)").unwrap();
+
+        source
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{stdin, BufRead, StdinLock};
+
+use brainfuck::frontend::{Location, Program};
+use brainfuck::interpreters::{basic, Procedures, RuntimeError};
+use brainfuck::io::{Console, IoConfig};
+use brainfuck::tape::{Cell, Tape, TapeConfig};
+
+/// Cells shown on each side of the memory pointer by `tape` without a radius.
+const DEFAULT_TAPE_RADIUS: usize = 8usize;
+
+/// Help for the `tape` command, which the REPL shares.
+pub fn tape_help() -> String {
+    format!(
+        "t, tape [RADIUS]     print the cells within RADIUS of the memory pointer (default {})",
+        DEFAULT_TAPE_RADIUS
+    )
+}
+
+/// Runs the `tape` command with its `radius` argument, if any, which the REPL shares.
+pub fn print_tape<C: Cell>(tape: &Tape<C>, radius: Option<&str>) {
+    match radius {
+        None => eprintln!("{}", tape.window(DEFAULT_TAPE_RADIUS)),
+        Some(radius) => match radius.parse::<usize>() {
+            Ok(radius) => eprintln!("{}", tape.window(radius)),
+            Err(_) => eprintln!("`{}` is not a number of cells", radius),
+        },
+    }
+}
+
+fn help() -> String {
+    format!(
+        "\
+s, step [N]          execute N instructions (default 1)
+c, continue          run until a breakpoint, a change to a watched cell, or the end
+b, break LOCATION    set a breakpoint at LINE or LINE:COLUMN
+d, delete LOCATION   remove the breakpoint at LINE or LINE:COLUMN
+w, watch CELL        stop whenever CELL changes
+u, unwatch CELL      stop watching CELL
+{}
+i, info              print the position, breakpoints and watched cells
+q, quit              stop debugging
+h, help              print this message
+An empty line repeats the previous command, and `#` in the source sets a breakpoint.",
+        tape_help()
+    )
+}
+
+/// A brainfuck program paused between instructions of the basic interpreter.
+struct Debugger<'a, C: Cell> {
+    program: &'a Program,
+    source: &'a [u8],
+    tape: Tape<C>,
+    console: Console<Box<dyn BufRead>>,
+    procedures: Procedures,
+    io_config: IoConfig,
+    instruction_pointer: usize,
+    steps: u64,
+    /// Indices of the instructions to stop before.
+    breakpoints: BTreeSet<usize>,
+    /// Watched cells, and the value each had when last checked.
+    watches: BTreeMap<usize, C>,
+}
+
+impl<C: Cell> Debugger<'_, C> {
+    fn is_finished(&self) -> bool {
+        self.instruction_pointer >= self.program.len()
+    }
+
+    /// The value of any cell, including cells past the end of a tape that has yet to grow.
+    fn cell(&self, index: usize) -> C {
+        self.tape.cells().get(index).copied().unwrap_or(C::ZERO)
+    }
+
+    fn location(&self, instruction: usize) -> Location {
+        Location::from_offset(self.source, self.program.offsets[instruction])
+    }
+
+    /// The first instruction at or after `LINE` or `LINE:COLUMN` in the source.
+    fn resolve(&self, argument: &str) -> Option<usize> {
+        let (line, column): (&str, &str) = argument.split_once(':').unwrap_or((argument, "1"));
+        let line: usize = line.parse::<usize>().ok()?.checked_sub(1usize)?;
+        let column: usize = column.parse::<usize>().ok()?.checked_sub(1usize)?;
+
+        let line_start: usize = if line == 0usize {
+            0usize
+        } else {
+            self.source
+                .iter()
+                .enumerate()
+                .filter(|&(_, &byte)| byte == b'\n')
+                .nth(line - 1usize)?
+                .0
+                + 1usize
+        };
+        let offset: usize = line_start + column;
+
+        let instruction: usize = self
+            .program
+            .offsets
+            .partition_point(|&instruction_offset| instruction_offset < offset);
+        (instruction < self.program.len()).then_some(instruction)
+    }
+
+    fn print_position(&self) {
+        if self.is_finished() {
+            eprintln!("program finished after {} steps", self.steps);
+            return;
+        }
+
+        let location: Location = self.location(self.instruction_pointer);
+        eprintln!(
+            "at {} `{}` (instruction {}, step {})\n{}",
+            location,
+            self.source[location.offset] as char,
+            self.instruction_pointer,
+            self.steps,
+            location.snippet(self.source)
+        );
+    }
+
+    fn print_info(&self) {
+        self.print_position();
+        eprintln!("memory pointer at cell {}", self.tape.pointer());
+
+        if self.breakpoints.is_empty() {
+            eprintln!("no breakpoints");
+        }
+        for &instruction in &self.breakpoints {
+            eprintln!("breakpoint at {}", self.location(instruction));
+        }
+
+        if self.watches.is_empty() {
+            eprintln!("no watched cells");
+        }
+        for &index in self.watches.keys() {
+            eprintln!("watching cell {} = {:?}", index, self.cell(index));
+        }
+    }
+
+    /// Executes up to `count` instructions, or until something stops execution if `None`.
+    ///
+    /// Breakpoints are only checked after the first instruction, so that continuing from a
+    /// breakpoint makes progress. Tape errors stop execution before the failing instruction and
+    /// leave the state intact for inspection, while I/O errors end the session.
+    fn resume(&mut self, count: Option<u64>) -> Result<(), RuntimeError> {
+        let mut executed: u64 = 0u64;
+
+        while !self.is_finished() && count.is_none_or(|count| executed < count) {
+            if executed > 0u64 && self.breakpoints.contains(&self.instruction_pointer) {
+                eprintln!("breakpoint");
+                break;
+            }
+
+            match basic::step(
+                self.program,
+                &mut self.tape,
+                &mut self.console,
+                &mut self.procedures,
+                self.io_config.eof,
+                self.instruction_pointer,
+            ) {
+                Ok(next) => self.instruction_pointer = next,
+                Err(RuntimeError::Tape(error)) => {
+                    eprintln!("error: {}", error);
+                    break;
+                }
+                Err(error) => return Err(error),
+            }
+            self.steps += 1u64;
+            executed += 1u64;
+
+            let changes: Vec<(usize, C, C)> = self
+                .watches
+                .iter()
+                .map(|(&index, &old)| (index, old, self.cell(index)))
+                .filter(|&(_, old, new)| old != new)
+                .collect();
+            for &(index, old, new) in &changes {
+                eprintln!("cell {} changed from {:?} to {:?}", index, old, new);
+                self.watches.insert(index, new);
+            }
+            if !changes.is_empty() {
+                break;
+            }
+        }
+
+        self.print_position();
+        Ok(())
+    }
+}
+
+/// Runs `program` under an interactive debugger reading commands from stdin, with `input` for the
+/// program's `,`. The program's own output goes to stdout, and everything the debugger prints goes
+/// to stderr.
+pub fn run<C: Cell>(
+    program: &Program,
+    source: &[u8],
+    tape_config: &TapeConfig,
+    io_config: &IoConfig,
+    input: Box<dyn BufRead>,
+) -> Result<(), RuntimeError> {
+    let mut debugger: Debugger<C> = Debugger {
+        program,
+        source,
+        tape: Tape::new(tape_config)?,
+        console: Console::with_reader(input, io_config),
+        procedures: Procedures::default(),
+        io_config: *io_config,
+        instruction_pointer: 0usize,
+        steps: 0u64,
+        breakpoints: BTreeSet::new(),
+        watches: BTreeMap::new(),
+    };
+    debugger.console.embed_input(&program.input);
+
+    for (offset, _) in source.iter().enumerate().filter(|&(_, &byte)| byte == b'#') {
+        let instruction: usize = program
+            .offsets
+            .partition_point(|&instruction_offset| instruction_offset < offset);
+        if instruction < program.len() {
+            debugger.breakpoints.insert(instruction);
+        }
+    }
+
+    eprintln!(
+        "{} instructions, {} breakpoint(s) from `#`, type `help` for a list of commands",
+        program.len(),
+        debugger.breakpoints.len()
+    );
+    debugger.print_position();
+
+    let mut commands: StdinLock = stdin().lock();
+    let mut previous_line: String = String::new();
+    loop {
+        debugger.console.flush()?;
+        eprint!("(bfdb) ");
+        let mut line: String = String::new();
+        if commands.read_line(&mut line)? == 0usize {
+            eprintln!();
+            break;
+        }
+        if line.trim().is_empty() {
+            line = previous_line.clone();
+        }
+
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        let argument: Option<&str> = words.next();
+
+        match (command, argument) {
+            ("s" | "step", None) => debugger.resume(Some(1u64))?,
+            ("s" | "step", Some(count)) => match count.parse::<u64>() {
+                Ok(count) => debugger.resume(Some(count))?,
+                Err(_) => eprintln!("`{}` is not a number of steps", count),
+            },
+            ("c" | "continue", _) => debugger.resume(None)?,
+            ("b" | "break", Some(location)) => match debugger.resolve(location) {
+                Some(instruction) => {
+                    debugger.breakpoints.insert(instruction);
+                    eprintln!("breakpoint at {}", debugger.location(instruction));
+                }
+                None => eprintln!("no instruction at or after {}", location),
+            },
+            ("d" | "delete", Some(location)) => match debugger.resolve(location) {
+                Some(instruction) if debugger.breakpoints.remove(&instruction) => {
+                    eprintln!("deleted breakpoint at {}", debugger.location(instruction));
+                }
+                _ => eprintln!("no breakpoint at {}", location),
+            },
+            ("w" | "watch", Some(index)) => match index.parse::<usize>() {
+                Ok(index) => {
+                    let value: C = debugger.cell(index);
+                    debugger.watches.insert(index, value);
+                    eprintln!("watching cell {} = {:?}", index, value);
+                }
+                Err(_) => eprintln!("`{}` is not a cell index", index),
+            },
+            ("u" | "unwatch", Some(index)) => {
+                match index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| debugger.watches.remove(&index))
+                {
+                    Some(_) => eprintln!("stopped watching cell {}", index),
+                    None => eprintln!("cell {} is not watched", index),
+                }
+            }
+            ("t" | "tape", radius) => print_tape(&debugger.tape, radius),
+            ("i" | "info", _) => debugger.print_info(),
+            ("q" | "quit", _) => break,
+            ("h" | "help", _) => eprintln!("{}", help()),
+            ("b" | "break" | "d" | "delete" | "w" | "watch" | "u" | "unwatch", None) => {
+                eprintln!("`{}` needs an argument, type `help` for usage", command)
+            }
+            _ => eprintln!(
+                "unknown command `{}`, type `help` for a list of commands",
+                command
+            ),
+        }
+
+        previous_line = line;
+    }
+
+    debugger.console.finish()?;
+
+    Ok(())
+}
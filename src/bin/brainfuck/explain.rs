@@ -0,0 +1,175 @@
+use std::fmt::Write as _;
+
+use brainfuck::frontend::Program;
+use brainfuck::ir::{self, Ir, Op};
+
+use crate::compilers::c::C;
+use crate::compilers::Target;
+
+const INDENT: &str = "    ";
+
+/// `target += amount`, or `target -= amount` for a negative amount.
+fn add(target: &str, amount: i32, operand: &str) -> String {
+    let operator: &str = if amount < 0i32 { "-=" } else { "+=" };
+    match (amount.unsigned_abs(), operand) {
+        (amount, "") => format!("{} {} {};", target, operator, amount),
+        (1u32, operand) => format!("{} {} {};", target, operator, operand),
+        (amount, operand) => format!("{} {} {} * {};", target, operator, operand, amount),
+    }
+}
+
+/// Whether the loop or procedure body between `start` and `end` leaves the memory pointer where
+/// it found it.
+fn is_balanced(ops: &[Op], start: usize, end: usize) -> bool {
+    let mut movement: isize = 0isize;
+    let mut i: usize = start + 1usize;
+
+    while i < end {
+        match ops[i] {
+            Op::Move(distance) => movement += distance,
+            Op::LoopStart(loop_end) if is_balanced(ops, i, loop_end) => i = loop_end,
+            Op::ScanLeft(_) | Op::ScanRight(_) | Op::LoopStart(_) | Op::Call => return false,
+            // A procedure body only moves the pointer once called, and any call in the body
+            // already makes it unbalanced.
+            Op::ProcedureStart(procedure_end) => i = procedure_end,
+            _ => (),
+        }
+        i += 1usize;
+    }
+
+    movement == 0isize
+}
+
+/// Pseudo-code under construction, with moves of the memory pointer held back as an offset for
+/// as long as possible, so that `>+>+<<` reads as `p[1] += 1; p[2] += 1;`.
+struct Explainer<'a> {
+    ops: &'a [Op],
+    text: String,
+    depth: usize,
+    /// How far the memory pointer has moved beyond `p`.
+    offset: isize,
+}
+
+impl Explainer<'_> {
+    fn line(&mut self, statement: &str) {
+        for _ in 0..self.depth {
+            self.text.push_str(INDENT);
+        }
+        writeln!(self.text, "{}", statement).unwrap();
+    }
+
+    /// Moves `p` by the offset held back, for code that needs the real memory pointer.
+    fn settle(&mut self) {
+        match self.offset {
+            0 => (),
+            offset if offset < 0isize => self.line(&format!("p -= {};", offset.unsigned_abs())),
+            offset => self.line(&format!("p += {};", offset)),
+        }
+        self.offset = 0isize;
+    }
+
+    /// Explains the ops from `start` up to `end`.
+    fn block(&mut self, start: usize, end: usize) {
+        let mut i: usize = start;
+
+        while i < end {
+            let current: String = C::indexed(self.offset);
+            // A cleared cell that is then adjusted is set to a constant.
+            if let (Op::SetZero, Some(&Op::Add(value))) = (self.ops[i], self.ops.get(i + 1usize)) {
+                self.line(&format!("{} = {};", current, value));
+                i += 2usize;
+                continue;
+            }
+
+            match self.ops[i] {
+                Op::Add(amount) => self.line(&add(&current, amount, "")),
+                Op::Move(distance) => self.offset += distance,
+                Op::SetZero => self.line(&format!("{} = 0;", current)),
+                Op::MulAdd { offset, factor } => {
+                    self.line(&add(&C::indexed(self.offset + offset), factor, &current))
+                }
+                Op::ScanLeft(stride) => {
+                    self.settle();
+                    self.line(&format!("while (p[0]) p -= {};", stride));
+                }
+                Op::ScanRight(stride) => {
+                    self.settle();
+                    self.line(&format!("while (p[0]) p += {};", stride));
+                }
+                Op::Output => self.line(&format!("putchar({});", current)),
+                Op::Input => self.line(&format!("{} = getchar();", current)),
+                Op::LoopStart(loop_end) if is_balanced(self.ops, i, loop_end) => {
+                    // A body that ends by clearing the cell it tests runs at most once.
+                    let keyword: &str = match self.ops[loop_end - 1usize] {
+                        Op::SetZero => "if",
+                        _ => "while",
+                    };
+                    self.line(&format!("{} ({}) {{", keyword, current));
+                    self.depth += 1usize;
+                    self.block(i + 1usize, loop_end);
+                    self.depth -= 1usize;
+                    self.line("}");
+                    i = loop_end;
+                }
+                Op::LoopStart(loop_end) => {
+                    self.settle();
+                    self.line("while (p[0]) {");
+                    self.depth += 1usize;
+                    self.block(i + 1usize, loop_end);
+                    self.settle();
+                    self.depth -= 1usize;
+                    self.line("}");
+                    i = loop_end;
+                }
+                Op::Dump => {
+                    self.settle();
+                    self.line("dump();");
+                }
+                Op::ProcedureStart(procedure_end) => {
+                    self.line(&format!("procedure[{}] = {{", current));
+                    // The body runs wherever the procedure is called from.
+                    let offset: isize = self.offset;
+                    self.offset = 0isize;
+                    self.depth += 1usize;
+                    self.block(i + 1usize, procedure_end);
+                    self.settle();
+                    self.depth -= 1usize;
+                    self.offset = offset;
+                    self.line("};");
+                    i = procedure_end;
+                }
+                Op::Call => {
+                    self.settle();
+                    self.line("procedure[p[0]]();");
+                }
+                Op::LoopEnd(_) | Op::ProcedureEnd => {
+                    unreachable!("ends are skipped with their start")
+                }
+            }
+            i += 1usize;
+        }
+    }
+}
+
+/// The program as C-like pseudo-code over the optimized ops, where `p` is the memory pointer and
+/// `p[n]` the cell `n` cells to its right. Loops that keep the pointer in place index cells from
+/// where they started, and loops that always end by clearing their cell become `if`.
+pub fn explain(program: &Program) -> String {
+    let ir: Ir = ir::build(program);
+    let mut explainer: Explainer = Explainer {
+        ops: &ir.ops,
+        text: String::new(),
+        depth: 0usize,
+        offset: 0isize,
+    };
+
+    if !program.input.is_empty() {
+        explainer.line(&format!(
+            "// reads \"{}\" before stdin",
+            program.input.escape_ascii()
+        ));
+    }
+    explainer.block(0usize, ir.ops.len());
+
+    explainer.text
+}
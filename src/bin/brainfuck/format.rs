@@ -0,0 +1,149 @@
+use brainfuck::frontend::{self, Dialect, Instruction, Program};
+
+const INDENT: &[u8] = b"  ";
+
+/// The program's instructions without comments or whitespace, and without adjacent pairs like
+/// `+-` and `<>` that undo each other. Cancelling `<>` also drops a step off the tape that the
+/// original program would have taken.
+pub fn minify(program: &Program) -> Vec<u8> {
+    let mut minified: Vec<u8> = Vec::with_capacity(program.len());
+
+    for instruction in &program.instructions {
+        let byte: u8 = instruction.to_byte();
+        match (minified.last(), byte) {
+            (Some(b'+'), b'-') | (Some(b'-'), b'+') | (Some(b'>'), b'<') | (Some(b'<'), b'>') => {
+                minified.pop();
+            }
+            _ => minified.push(byte),
+        }
+    }
+
+    if !program.input.is_empty() {
+        minified.push(b'!');
+        minified.extend_from_slice(&program.input);
+    }
+    minified
+}
+
+/// Lines of formatted source under construction.
+struct Layout {
+    formatted: Vec<u8>,
+    line: Vec<u8>,
+    depth: usize,
+    /// Inside a loop or procedure without nested ones, which stays on one line.
+    in_innermost: bool,
+    /// Whether the last byte on the line is part of a comment.
+    in_comment: bool,
+    line_has_comment: bool,
+    previous_line_had_comment: bool,
+    /// Whitespace was skipped since the last byte on the line.
+    pending_space: bool,
+}
+
+impl Layout {
+    fn push(&mut self, byte: u8, is_comment: bool) {
+        // Whitespace only survives where it separates a comment from something else.
+        if self.pending_space && !self.line.is_empty() && (is_comment || self.in_comment) {
+            self.line.push(b' ');
+        }
+        self.line.push(byte);
+        self.in_comment = is_comment;
+        self.line_has_comment |= is_comment;
+        self.pending_space = false;
+    }
+
+    fn end_line(&mut self) {
+        if !self.line.is_empty() {
+            for _ in 0..self.depth {
+                self.formatted.extend_from_slice(INDENT);
+            }
+            self.formatted.append(&mut self.line);
+            self.formatted.push(b'\n');
+            self.previous_line_had_comment = self.line_has_comment;
+        }
+        self.in_comment = false;
+        self.line_has_comment = false;
+        self.pending_space = false;
+    }
+
+    /// Ends a line of comments where the source does, and keeps a blank line after one, so
+    /// that prose stays readable. Newlines between instructions are dropped.
+    fn newline(&mut self) {
+        if self.line_has_comment {
+            self.end_line();
+        } else if self.line.is_empty() && self.previous_line_had_comment {
+            self.formatted.push(b'\n');
+            self.previous_line_had_comment = false;
+        } else {
+            self.pending_space = true;
+        }
+    }
+}
+
+/// Whether a body ends at the next bracket without any comments, so that it fits on one line.
+fn is_innermost(body: &[u8], dialect: &Dialect) -> bool {
+    for &byte in body {
+        match Instruction::from_byte(byte, dialect) {
+            Some(Instruction::LoopEnd | Instruction::ProcedureEnd) => return true,
+            Some(Instruction::LoopStart | Instruction::ProcedureStart) => return false,
+            Some(_) => (),
+            None if byte.is_ascii_whitespace() => (),
+            None => return false,
+        }
+    }
+    false
+}
+
+/// The source with the body of every loop and procedure that nests others or holds comments on
+/// its own lines, indented by nesting depth. Instructions between brackets are joined onto one
+/// line, while comments keep their words and line breaks. The program must parse, so that the
+/// brackets match.
+pub fn format(source: &[u8], dialect: &Dialect) -> Vec<u8> {
+    let (code, input): (&[u8], Option<&[u8]>) = frontend::split_input(source, dialect);
+    let mut layout: Layout = Layout {
+        formatted: Vec::with_capacity(source.len()),
+        line: Vec::new(),
+        depth: 0usize,
+        in_innermost: false,
+        in_comment: false,
+        line_has_comment: false,
+        previous_line_had_comment: false,
+        pending_space: false,
+    };
+
+    for (offset, &byte) in code.iter().enumerate() {
+        match Instruction::from_byte(byte, dialect) {
+            Some(Instruction::LoopStart | Instruction::ProcedureStart)
+                if is_innermost(&code[offset + 1usize..], dialect) =>
+            {
+                layout.push(byte, false);
+                layout.in_innermost = true;
+            }
+            Some(Instruction::LoopEnd | Instruction::ProcedureEnd) if layout.in_innermost => {
+                layout.push(byte, false);
+                layout.in_innermost = false;
+            }
+            Some(Instruction::LoopStart | Instruction::ProcedureStart) => {
+                layout.push(byte, false);
+                layout.end_line();
+                layout.depth += 1usize;
+            }
+            Some(Instruction::LoopEnd | Instruction::ProcedureEnd) => {
+                layout.end_line();
+                layout.depth -= 1usize;
+                layout.push(byte, false);
+            }
+            Some(_) => layout.push(byte, false),
+            None if byte == b'\n' => layout.newline(),
+            None if byte.is_ascii_whitespace() => layout.pending_space = true,
+            None => layout.push(byte, true),
+        }
+    }
+    layout.end_line();
+
+    if let Some(input) = input {
+        layout.formatted.push(b'!');
+        layout.formatted.extend_from_slice(input);
+    }
+    layout.formatted
+}
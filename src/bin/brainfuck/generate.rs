@@ -0,0 +1,99 @@
+/// How to change the value cell by a given amount.
+enum Adjustment {
+    /// A run of `+` or `-`.
+    Run(u32),
+    /// A loop adding `factor` to the value cell `count` times, then a run for the remainder.
+    Loop {
+        count: u32,
+        factor: u32,
+        remainder: i32,
+    },
+}
+
+impl Adjustment {
+    fn len(&self) -> u32 {
+        match *self {
+            Adjustment::Run(amount) => amount,
+            // `<`, `[>`, `<-]>` and the runs of the count, factor and remainder.
+            Adjustment::Loop {
+                count,
+                factor,
+                remainder,
+            } => 7u32 + count + factor + remainder.unsigned_abs(),
+        }
+    }
+
+    /// The shortest way to change the value cell by `delta`.
+    fn shortest(delta: u32) -> Adjustment {
+        let mut shortest: Adjustment = Adjustment::Run(delta);
+
+        for count in 2u32..=delta {
+            for factor in [delta / count, delta / count + 1u32] {
+                let candidate: Adjustment = Adjustment::Loop {
+                    count,
+                    factor,
+                    remainder: delta as i32 - (count * factor) as i32,
+                };
+                if factor > 0u32 && candidate.len() < shortest.len() {
+                    shortest = candidate;
+                }
+            }
+        }
+
+        shortest
+    }
+
+    fn write(&self, increment: u8, decrement: u8, program: &mut Vec<u8>) {
+        let run = |amount: i32, program: &mut Vec<u8>| {
+            let byte: u8 = if amount < 0i32 { decrement } else { increment };
+            program.extend(std::iter::repeat_n(byte, amount.unsigned_abs() as usize));
+        };
+
+        match *self {
+            Adjustment::Run(amount) => run(amount as i32, program),
+            Adjustment::Loop {
+                count,
+                factor,
+                remainder,
+            } => {
+                program.push(b'<');
+                program.extend(std::iter::repeat_n(b'+', count as usize));
+                program.extend_from_slice(b"[>");
+                run(factor as i32, program);
+                program.extend_from_slice(b"<-]>");
+                run(remainder, program);
+            }
+        }
+    }
+}
+
+/// A brainfuck program that prints `text`.
+///
+/// The program keeps a loop counter in the first cell and the byte being printed in the second,
+/// and reaches each byte from the previous one with whichever of a run of `+` or `-` and a
+/// multiplication loop is shorter. A loop can take the value past 0 or 255 before its remainder
+/// brings it back, which cells of any width undo alike, so the program prints the same with any
+/// cell width.
+pub fn generate(text: &[u8]) -> Vec<u8> {
+    let mut program: Vec<u8> = vec![b'>'];
+    let mut value: u8 = 0u8;
+
+    for &byte in text {
+        let adjustment: Adjustment = Adjustment::shortest(byte.abs_diff(value) as u32);
+        if byte >= value {
+            adjustment.write(b'+', b'-', &mut program);
+        } else {
+            adjustment.write(b'-', b'+', &mut program);
+        }
+        program.push(b'.');
+        value = byte;
+    }
+
+    // The memory pointer starts on the counter, so a first loop needs no `><` to reach it.
+    if program.starts_with(b"><") {
+        program.drain(..2usize);
+    }
+
+    program.push(b'\n');
+    program
+}
@@ -0,0 +1,723 @@
+use clap::error::ErrorKind;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command, ValueHint};
+
+use std::env::consts::EXE_EXTENSION;
+use std::fs::{read, write, File};
+use std::io::{empty, stdin, stdout, BufRead, BufReader, Read, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::thread::available_parallelism;
+use std::time::Duration;
+
+use brainfuck::frontend::{self, Dialect, Program};
+use brainfuck::interpreters::{self, Mode, RuntimeError};
+use brainfuck::io::{Eof, IoConfig, IoMode};
+use brainfuck::limits::Limits;
+use brainfuck::profile::Profile;
+use brainfuck::tape::{Bounds, Cell, TapeConfig};
+
+use crate::analysis::Analysis;
+use crate::batch::{BatchConfig, Outcome};
+use crate::compilers::asm::Asm;
+use crate::compilers::c::C;
+use crate::compilers::wat::Wat;
+use crate::compilers::CompileError;
+
+const MEMORY_SIZE: usize = 2usize.pow(15);
+
+mod analysis;
+mod batch;
+mod bench;
+mod compilers;
+mod debugger;
+mod explain;
+mod format;
+mod generate;
+mod repl;
+
+/// Calls the generic `function` with the arguments in parentheses, for cells of the width
+/// `--cell-bits` asks for.
+macro_rules! with_cell_bits {
+    ($sub_matches:expr, $($function:ident)::+ ($($argument:expr),* $(,)?)) => {
+        match $sub_matches.get_one::<String>("cell-bits").unwrap().as_str() {
+            "16" => $($function)::+::<u16>($($argument),*),
+            "32" => $($function)::+::<u32>($($argument),*),
+            _ => $($function)::+::<u8>($($argument),*),
+        }
+    };
+}
+
+fn interpret<C: Cell>(
+    mode: Mode,
+    program: &Program,
+    tape_config: &TapeConfig,
+    io_config: &IoConfig,
+    limits: &Limits,
+    input: Box<dyn BufRead>,
+) -> Result<(), RuntimeError> {
+    interpreters::run::<C, _>(mode, program, tape_config, io_config, limits, input)
+}
+
+/// Runs the program with the optimized interpreter and prints a profile to stderr once it stops.
+fn profile<C: Cell>(
+    program: &Program,
+    source: &[u8],
+    dialect: &Dialect,
+    tape_config: &TapeConfig,
+    io_config: &IoConfig,
+    limits: &Limits,
+    input: Box<dyn BufRead>,
+) -> Result<(), RuntimeError> {
+    let (profile, result): (Profile, Result<(), RuntimeError>) =
+        interpreters::optimized::profile::<C, _>(program, tape_config, io_config, limits, input);
+    eprint!("{}", profile.report(source, dialect));
+    result
+}
+
+fn tape_args() -> [Arg; 5] {
+    [
+        Arg::new("tape-size")
+            .long("tape-size")
+            .value_name("CELLS")
+            .value_parser(value_parser!(u64).range(1..))
+            .help(format!(
+                "Set number of cells on the tape [default: {}]",
+                MEMORY_SIZE
+            )),
+        Arg::new("grow")
+            .long("grow")
+            .action(ArgAction::SetTrue)
+            .help("Grow the tape when the memory pointer moves past its end"),
+        Arg::new("max-tape-size")
+            .long("max-tape-size")
+            .value_name("CELLS")
+            .value_parser(value_parser!(u64).range(1..))
+            .help("Never grow the tape beyond CELLS cells"),
+        Arg::new("bounds")
+            .long("bounds")
+            .value_name("BEHAVIOUR")
+            .value_parser(["error", "wrap"])
+            .default_value("error")
+            .help("Set behaviour when the memory pointer leaves the tape"),
+        Arg::new("cell-bits")
+            .long("cell-bits")
+            .value_name("BITS")
+            .value_parser(["8", "16", "32"])
+            .default_value("8")
+            .help("Set width of tape cells"),
+    ]
+}
+
+fn parse_tape_config(sub_matches: &ArgMatches) -> TapeConfig {
+    TapeConfig {
+        length: sub_matches
+            .get_one::<u64>("tape-size")
+            .map_or(MEMORY_SIZE, |&length| length as usize),
+        growable: sub_matches.get_flag("grow"),
+        max_length: sub_matches
+            .get_one::<u64>("max-tape-size")
+            .map_or(usize::MAX, |&length| length as usize),
+        bounds: match sub_matches.get_one::<String>("bounds").unwrap().as_str() {
+            "wrap" => Bounds::Wrap,
+            _ => Bounds::Error,
+        },
+    }
+}
+
+/// Parses `--timeout`, which must be a finite, non-negative number of seconds.
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value.parse::<f64>().map_err(|error| error.to_string())?;
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("expected seconds from 0 to {}", Duration::MAX.as_secs()))
+}
+
+fn limit_args() -> [Arg; 2] {
+    [
+        Arg::new("max-steps")
+            .long("max-steps")
+            .value_name("STEPS")
+            .value_parser(value_parser!(u64))
+            .help("Stop the program after STEPS instructions, or optimized operations and scan moves outside basic mode"),
+        Arg::new("timeout")
+            .long("timeout")
+            .value_name("SECONDS")
+            .value_parser(parse_timeout)
+            .help("Stop the program after SECONDS seconds"),
+    ]
+}
+
+fn parse_limits(sub_matches: &ArgMatches) -> Limits {
+    Limits {
+        max_steps: sub_matches.get_one::<u64>("max-steps").copied(),
+        timeout: sub_matches.get_one::<Duration>("timeout").copied(),
+    }
+}
+
+fn dialect_arg() -> Arg {
+    Arg::new("dialect")
+        .long("dialect")
+        .value_name("EXTENSIONS")
+        .value_parser(["dump", "embedded-input", "pbrain"])
+        .value_delimiter(',')
+        .action(ArgAction::Append)
+        .help("Enable extensions: `#` to dump the tape, `!` before input embedded in the source, and pbrain procedures")
+}
+
+fn parse_dialect(sub_matches: &ArgMatches) -> Dialect {
+    let extensions: Vec<&String> = sub_matches
+        .try_get_many::<String>("dialect")
+        .ok()
+        .flatten()
+        .map_or(Vec::new(), |extensions| extensions.collect());
+    let enabled = |extension: &str| -> bool { extensions.iter().any(|&name| name == extension) };
+
+    Dialect {
+        dump: enabled("dump"),
+        embedded_input: enabled("embedded-input"),
+        procedures: enabled("pbrain"),
+    }
+}
+
+fn source_output_arg() -> Arg {
+    Arg::new("output")
+        .short('o')
+        .long("output")
+        .value_name("FILENAME")
+        .value_hint(ValueHint::FilePath)
+        .help(
+            "Write the brainfuck source to FILENAME instead of stdout, which may be the input file",
+        )
+}
+
+/// Writes brainfuck source to the `--output` file, or to stdout.
+fn write_source(sub_matches: &ArgMatches, source: &[u8]) -> std::io::Result<()> {
+    match sub_matches.get_one::<String>("output") {
+        Some(output_path) => write(output_path, source),
+        None => stdout().lock().write_all(source),
+    }
+}
+
+/// Adds the path of the file that failed to open to `error`.
+fn naming(path: &str, error: std::io::Error) -> std::io::Error {
+    std::io::Error::new(error.kind(), format!("{}: {}", path, error))
+}
+
+/// The brainfuck source from `--eval`, stdin or the `INPUT` file, with the name to show for it in
+/// diagnostics.
+fn read_source(matches: &ArgMatches) -> std::io::Result<(String, Vec<u8>)> {
+    if let Some(code) = matches.get_one::<String>("eval") {
+        return Ok(("<eval>".to_string(), code.as_bytes().to_vec()));
+    }
+
+    match matches.get_one::<String>("INPUT").unwrap().as_str() {
+        "-" => {
+            let mut source: Vec<u8> = Vec::new();
+            stdin().lock().read_to_end(&mut source)?;
+            Ok(("<stdin>".to_string(), source))
+        }
+        path => Ok((
+            path.to_string(),
+            read(Path::new(path)).map_err(|error| naming(path, error))?,
+        )),
+    }
+}
+
+fn program_input_arg() -> Arg {
+    Arg::new("input")
+        .long("input")
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .help("Read what the program takes with `,` from FILE instead of stdin")
+}
+
+/// Where the program reads its input: the `--input` file, or stdin.
+fn open_program_input(sub_matches: &ArgMatches) -> std::io::Result<Box<dyn BufRead>> {
+    match sub_matches.get_one::<String>("input").map(String::as_str) {
+        Some("-") | None => Ok(Box::new(stdin().lock())),
+        Some(path) => Ok(Box::new(BufReader::new(
+            File::open(path).map_err(|error| naming(path, error))?,
+        ))),
+    }
+}
+
+fn io_args() -> [Arg; 2] {
+    [
+        Arg::new("eof")
+            .long("eof")
+            .value_name("BEHAVIOUR")
+            .value_parser(["unchanged", "zero", "minus-one"])
+            .default_value("unchanged")
+            .help("Set what `,` stores in the current cell once input is exhausted"),
+        Arg::new("raw").long("raw").action(ArgAction::SetTrue).help(
+            "Pass all bytes through unchanged and only flush output when reading input or exiting",
+        ),
+    ]
+}
+
+fn parse_io_config(sub_matches: &ArgMatches) -> IoConfig {
+    IoConfig {
+        mode: if sub_matches.get_flag("raw") {
+            IoMode::Raw
+        } else {
+            IoMode::Text
+        },
+        eof: match sub_matches.get_one::<String>("eof").unwrap().as_str() {
+            "zero" => Eof::Zero,
+            "minus-one" => Eof::MinusOne,
+            _ => Eof::Unchanged,
+        },
+    }
+}
+
+fn main() {
+    let mut cmd: Command = Command::new("brainfuck")
+        .version("1.0.0")
+        .author("Melker Widen")
+        .about("A bundled interpreter and compiler for brainfuck programs.")
+        .propagate_version(true)
+        .subcommand_required(true)
+        .arg(
+            Arg::new("INPUT")
+                .value_name("INPUT")
+                .value_hint(ValueHint::FilePath)
+                .help("Brainfuck source file, or `-` to read it from stdin. Required by every subcommand but generate, repl and run-all, unless --eval is given.")
+                .index(1usize),
+        )
+        .arg(
+            Arg::new("eval")
+                .short('e')
+                .long("eval")
+                .value_name("CODE")
+                .conflicts_with("INPUT")
+                .help("Use CODE as the brainfuck source instead of reading a file"),
+        )
+        .subcommand(
+            Command::new("interpreter")
+                .short_flag('I')
+                .about("Use as an interpreter")
+                .arg(
+                    Arg::new("mode")
+                        .short('m')
+                        .long("mode")
+                        .value_name("MODE")
+                        .value_parser(["basic", "optimized", "jit"])
+                        .default_value("optimized")
+                        .help("Set optimization level of interpreter"),
+                )
+                .arg(
+                    Arg::new("profile")
+                        .long("profile")
+                        .action(ArgAction::SetTrue)
+                        .help("Count the steps of every optimized op and loop, as --max-steps counts them, and report the hottest on exit"),
+                )
+                .arg(dialect_arg())
+                .arg(program_input_arg())
+                .args(tape_args())
+                .args(limit_args())
+                .args(io_args()),
+        )
+        .subcommand(
+            Command::new("compiler")
+                .short_flag('C')
+                .about("Use as a compiler")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILENAME")
+                        .value_hint(ValueHint::FilePath)
+                        .help("Set output path of compiled brainfuck program"),
+                )
+                .arg(
+                    Arg::new("emit-source")
+                        .long("emit-source")
+                        .value_name("PATH")
+                        .value_hint(ValueHint::FilePath)
+                        .help("Write the transpiled Rust source to PATH and keep it"),
+                )
+                .arg(
+                    Arg::new("target")
+                        .short('t')
+                        .long("target")
+                        .value_name("TARGET")
+                        .value_parser(["rust", "c", "asm", "wat"])
+                        .default_value("rust")
+                        .help("Build an executable through rustc, or emit C, x86-64 assembly or WebAssembly text for WASI"),
+                )
+                .arg(dialect_arg())
+                .args(io_args()),
+        )
+        .subcommand(
+            Command::new("debug")
+                .short_flag('D')
+                .about("Step through a program with breakpoints, watched cells and a view of the tape")
+                .arg(dialect_arg())
+                .args(tape_args())
+                .args(io_args())
+                .arg(
+                    Arg::new("input")
+                        .long("input")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .help("Read what the program takes with `,` from FILE, as stdin holds the commands [default: no input]"),
+                ),
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("Indent loops by nesting depth, keeping comments")
+                .arg(source_output_arg())
+                .arg(dialect_arg()),
+        )
+        .subcommand(
+            Command::new("minify")
+                .about("Strip everything but instructions and cancel pairs like `+-` and `<>`")
+                .arg(source_output_arg())
+                .arg(dialect_arg()),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Time the program in every interpreter mode and compiled, with the same input for each")
+                .arg(program_input_arg())
+                .args(io_args()),
+        )
+        .subcommand(
+            Command::new("check")
+                .about("Report loops that never run, pointer movement and tape bounds without running the program")
+                .arg(dialect_arg()),
+        )
+        .subcommand(
+            Command::new("explain")
+                .about("Print the optimized program as C-like pseudo-code")
+                .arg(dialect_arg()),
+        )
+        .subcommand(
+            Command::new("repl")
+                .about("Run lines of brainfuck as they are entered, on a tape that persists between them")
+                .arg(dialect_arg())
+                .args(tape_args())
+                .args(limit_args())
+                .args(io_args()),
+        )
+        .subcommand(
+            Command::new("run-all")
+                .about("Run every .b file in DIR in parallel with the optimized interpreter and summarize the results")
+                .arg(
+                    Arg::new("DIR")
+                        .value_hint(ValueHint::DirPath)
+                        .help("Directory of programs, each reading NAME.in next to it if there is one")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("inputs")
+                        .long("inputs")
+                        .value_name("DIR")
+                        .value_hint(ValueHint::DirPath)
+                        .help("Read the input of NAME.b from NAME.in in DIR instead"),
+                )
+                .arg(
+                    Arg::new("jobs")
+                        .short('j')
+                        .long("jobs")
+                        .value_name("N")
+                        .value_parser(value_parser!(u64).range(1..))
+                        .help("Run N programs at a time [default: number of CPUs]"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .action(ArgAction::SetTrue)
+                        .help("Print the summary as JSON instead of a table"),
+                )
+                .arg(dialect_arg())
+                .args(tape_args())
+                .args(limit_args())
+                .args(io_args()),
+        )
+        .subcommand(
+            Command::new("generate")
+                .about("Write a brainfuck program that prints TEXT")
+                .arg(
+                    Arg::new("TEXT")
+                        .help("Text for the program to print")
+                        .required(true),
+                )
+                .arg(source_output_arg()),
+        );
+
+    let matches: ArgMatches = cmd.get_matches_mut();
+
+    if let Some((subcommand @ ("generate" | "repl" | "run-all"), _)) = matches.subcommand() {
+        if matches.contains_id("INPUT") || matches.contains_id("eval") {
+            cmd.error(
+                ErrorKind::ArgumentConflict,
+                format!("{} does not take a source file", subcommand),
+            )
+            .exit();
+        }
+    }
+
+    if let Some(("generate", sub_matches)) = matches.subcommand() {
+        let text: &String = sub_matches.get_one::<String>("TEXT").unwrap();
+        if let Err(error) = write_source(sub_matches, &generate::generate(text.as_bytes())) {
+            eprintln!("error: {}", error);
+            exit(1);
+        }
+        return;
+    }
+
+    if let Some(("repl", sub_matches)) = matches.subcommand() {
+        let dialect: Dialect = parse_dialect(sub_matches);
+        let tape_config: TapeConfig = parse_tape_config(sub_matches);
+        let io_config: IoConfig = parse_io_config(sub_matches);
+        let limits: Limits = parse_limits(sub_matches);
+
+        let result: Result<(), RuntimeError> = with_cell_bits!(
+            sub_matches,
+            repl::run(&dialect, &tape_config, &io_config, &limits)
+        );
+
+        if let Err(error) = result {
+            eprintln!("error: {}", error);
+            exit(1);
+        }
+        return;
+    }
+
+    if let Some(("run-all", sub_matches)) = matches.subcommand() {
+        let directory: &Path = Path::new(sub_matches.get_one::<String>("DIR").unwrap());
+        let config: BatchConfig = BatchConfig {
+            dialect: parse_dialect(sub_matches),
+            tape_config: parse_tape_config(sub_matches),
+            io_config: parse_io_config(sub_matches),
+            limits: parse_limits(sub_matches),
+            input_directory: sub_matches.get_one::<String>("inputs").map(PathBuf::from),
+            jobs: sub_matches.get_one::<u64>("jobs").map_or_else(
+                || available_parallelism().map_or(1usize, NonZeroUsize::get),
+                |&jobs| jobs as usize,
+            ),
+        };
+
+        let result: std::io::Result<Vec<Outcome>> =
+            with_cell_bits!(sub_matches, batch::run(directory, &config));
+        let outcomes: Vec<Outcome> = match result {
+            Ok(outcomes) => outcomes,
+            Err(error) => {
+                eprintln!("error: {}", naming(&directory.to_string_lossy(), error));
+                exit(1);
+            }
+        };
+
+        if sub_matches.get_flag("json") {
+            print!("{}", batch::json(&outcomes));
+        } else {
+            print!("{}", batch::table(&outcomes));
+        }
+        if outcomes.iter().any(|outcome| outcome.error.is_some()) {
+            exit(1);
+        }
+        return;
+    }
+
+    if !matches.contains_id("INPUT") && !matches.contains_id("eval") {
+        cmd.error(
+            ErrorKind::MissingRequiredArgument,
+            "the following required arguments were not provided:\n  <INPUT|--eval <CODE>>",
+        )
+        .exit();
+    }
+    // The debugger reads its commands from stdin, which must not be used up by the source.
+    let source_is_stdin: bool = matches
+        .get_one::<String>("INPUT")
+        .is_some_and(|path| path == "-");
+    if source_is_stdin && matches!(matches.subcommand(), Some(("debug", _))) {
+        cmd.error(
+            ErrorKind::ArgumentConflict,
+            "debug reads commands from stdin, so the source cannot be read from it",
+        )
+        .exit();
+    }
+
+    let (input, source): (String, Vec<u8>) = match read_source(&matches) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            exit(1);
+        }
+    };
+    let input: &str = &input;
+    let dialect: Dialect = matches
+        .subcommand()
+        .map_or(Dialect::default(), |(_, sub_matches)| {
+            parse_dialect(sub_matches)
+        });
+    let brainfuck_program: Program = match frontend::parse(&source, &dialect) {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("{}", error.render(input, &source));
+            exit(1);
+        }
+    };
+
+    match matches.subcommand() {
+        Some(("interpreter", sub_matches)) => {
+            let mode: Mode = match sub_matches.get_one::<String>("mode").unwrap().as_str() {
+                "basic" => Mode::Basic,
+                "jit" => Mode::Jit,
+                _ => Mode::Optimized,
+            };
+            let tape_config: TapeConfig = parse_tape_config(sub_matches);
+
+            let io_config: IoConfig = parse_io_config(sub_matches);
+            let limits: Limits = parse_limits(sub_matches);
+
+            let program_input: Box<dyn BufRead> = match open_program_input(sub_matches) {
+                Ok(program_input) => program_input,
+                Err(error) => {
+                    eprintln!("error: {}", error);
+                    exit(1);
+                }
+            };
+
+            let result: Result<(), RuntimeError> = match (mode, sub_matches.get_flag("profile")) {
+                (Mode::Optimized, true) => with_cell_bits!(
+                    sub_matches,
+                    profile(
+                        &brainfuck_program,
+                        &source,
+                        &dialect,
+                        &tape_config,
+                        &io_config,
+                        &limits,
+                        program_input,
+                    )
+                ),
+                (_, true) => Err(RuntimeError::Unsupported(
+                    "`--profile` only works with the optimized interpreter",
+                )),
+                (_, false) => with_cell_bits!(
+                    sub_matches,
+                    interpret(
+                        mode,
+                        &brainfuck_program,
+                        &tape_config,
+                        &io_config,
+                        &limits,
+                        program_input,
+                    )
+                ),
+            };
+
+            if let Err(error) = result {
+                eprintln!("{}", error.render(input, &source));
+                exit(1);
+            }
+        }
+        Some(("compiler", sub_matches)) => {
+            let target: &str = sub_matches.get_one::<String>("target").unwrap();
+            let output_path: String = sub_matches
+                .get_one::<String>("output")
+                .cloned()
+                .unwrap_or_else(|| -> String {
+                    if source_is_stdin || !matches.contains_id("INPUT") {
+                        cmd.error(
+                            ErrorKind::MissingRequiredArgument,
+                            "--output is required when the source is not read from a file",
+                        )
+                        .exit();
+                    }
+                    let mut path_buf: PathBuf = PathBuf::from(input);
+                    path_buf.set_extension(match target {
+                        "c" => "c",
+                        "asm" => "s",
+                        "wat" => "wat",
+                        _ => EXE_EXTENSION,
+                    });
+                    path_buf.file_name().unwrap().to_string_lossy().into_owned()
+                });
+            let io_config: IoConfig = parse_io_config(sub_matches);
+
+            let result: Result<(), CompileError> = match target {
+                "c" => compilers::compile::<C>(&brainfuck_program, &output_path, &io_config),
+                "asm" => compilers::compile::<Asm>(&brainfuck_program, &output_path, &io_config),
+                "wat" => compilers::compile::<Wat>(&brainfuck_program, &output_path, &io_config),
+                _ => compilers::optimized::compile(
+                    &brainfuck_program,
+                    &output_path,
+                    &io_config,
+                    sub_matches.get_one::<String>("emit-source"),
+                ),
+            };
+
+            if let Err(error) = result {
+                eprintln!("error: {}", error);
+                exit(1);
+            }
+        }
+        Some(("debug", sub_matches)) => {
+            let tape_config: TapeConfig = parse_tape_config(sub_matches);
+            let io_config: IoConfig = parse_io_config(sub_matches);
+            let program_input: Box<dyn BufRead> = match sub_matches.get_one::<String>("input") {
+                Some(path) => match File::open(path) {
+                    Ok(file) => Box::new(BufReader::new(file)),
+                    Err(error) => {
+                        eprintln!("error: {}", naming(path, error));
+                        exit(1);
+                    }
+                },
+                None => Box::new(empty()),
+            };
+
+            let result: Result<(), RuntimeError> = with_cell_bits!(
+                sub_matches,
+                debugger::run(
+                    &brainfuck_program,
+                    &source,
+                    &tape_config,
+                    &io_config,
+                    program_input
+                )
+            );
+
+            if let Err(error) = result {
+                eprintln!("error: {}", error);
+                exit(1);
+            }
+        }
+        Some(("bench", sub_matches)) => {
+            let mut program_input: Vec<u8> = Vec::new();
+            if let Err(error) = open_program_input(sub_matches)
+                .and_then(|mut reader| reader.read_to_end(&mut program_input))
+            {
+                eprintln!("error: {}", error);
+                exit(1);
+            }
+            bench::run(
+                &brainfuck_program,
+                &parse_io_config(sub_matches),
+                &program_input,
+            );
+        }
+        Some(("check", _)) => {
+            let analysis: Analysis = analysis::analyze(&brainfuck_program);
+            print!("{}", analysis.report(&brainfuck_program, input, &source));
+            if analysis.has_errors() {
+                exit(1);
+            }
+        }
+        Some(("explain", _)) => print!("{}", explain::explain(&brainfuck_program)),
+        Some(("fmt", sub_matches)) => {
+            if let Err(error) = write_source(sub_matches, &format::format(&source, &dialect)) {
+                eprintln!("error: {}", error);
+                exit(1);
+            }
+        }
+        Some(("minify", sub_matches)) => {
+            if let Err(error) = write_source(sub_matches, &format::minify(&brainfuck_program)) {
+                eprintln!("error: {}", error);
+                exit(1);
+            }
+        }
+        _ => (),
+    }
+}
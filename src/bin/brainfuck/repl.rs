@@ -0,0 +1,87 @@
+use brainfuck::frontend::{self, Dialect, ParseError, Program};
+use brainfuck::interpreters::{basic, RuntimeError};
+use brainfuck::io::{Console, IoConfig};
+use brainfuck::limits::Limits;
+use brainfuck::tape::{Cell, Tape, TapeConfig};
+
+use crate::debugger::{print_tape, tape_help};
+
+/// Name of the source in diagnostics.
+const SOURCE_NAME: &str = "<repl>";
+
+fn help() -> String {
+    format!(
+        "\
+Every line runs as soon as it is entered, and a loop left open continues on the next line.
+A line starting with one of these commands is a command instead:
+{}
+r, reset             clear the tape and move the memory pointer back to cell 0
+q, quit              leave the REPL
+h, help              print this message",
+        tape_help()
+    )
+}
+
+/// Runs lines of brainfuck from stdin with the basic interpreter, on a tape and memory pointer
+/// that persist between lines. The program's output goes to stdout, and everything else to
+/// stderr. Procedures are only defined for the line that defines them.
+pub fn run<C: Cell>(
+    dialect: &Dialect,
+    tape_config: &TapeConfig,
+    io_config: &IoConfig,
+    limits: &Limits,
+) -> Result<(), RuntimeError> {
+    let mut tape: Tape<C> = Tape::new(tape_config)?;
+    let mut console: Console = Console::new(io_config);
+    // Lines entered since the last one that ran, which leave a loop or procedure open.
+    let mut pending: String = String::new();
+
+    eprintln!("type `help` for a list of commands");
+    loop {
+        eprint!("{}", if pending.is_empty() { "bf> " } else { "... " });
+        let Some(line) = console.read_line()? else {
+            eprintln!();
+            break;
+        };
+
+        // Commands are letters only, so none of them could have been a line of brainfuck.
+        let mut words = line.split_whitespace();
+        match (pending.is_empty(), words.next(), words.next()) {
+            (true, Some("t" | "tape"), radius) => print_tape(&tape, radius),
+            (true, Some("r" | "reset"), _) => {
+                tape = Tape::new(tape_config)?;
+                eprintln!("tape cleared");
+            }
+            (true, Some("q" | "quit"), _) => break,
+            (true, Some("h" | "help"), _) => eprintln!("{}", help()),
+            _ => {
+                pending.push_str(&line);
+                let program: Program = match frontend::parse(pending.as_bytes(), dialect) {
+                    Ok(program) => program,
+                    Err(
+                        ParseError::UnmatchedLoopStart(_) | ParseError::UnmatchedProcedureStart(_),
+                    ) => continue,
+                    Err(error) => {
+                        eprintln!("{}", error.render(SOURCE_NAME, pending.as_bytes()));
+                        pending.clear();
+                        continue;
+                    }
+                };
+
+                console.embed_input(&program.input);
+                match basic::execute(&program, &mut tape, &mut console, io_config.eof, limits) {
+                    Ok(_) => (),
+                    Err(RuntimeError::Io(error)) => return Err(RuntimeError::Io(error)),
+                    // The tape stays as the line left it.
+                    Err(error) => eprintln!("{}", error.render(SOURCE_NAME, pending.as_bytes())),
+                }
+                console.flush()?;
+                pending.clear();
+            }
+        }
+    }
+
+    console.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A single brainfuck instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    MoveRight,
+    MoveLeft,
+    Increment,
+    Decrement,
+    Output,
+    Input,
+    LoopStart,
+    LoopEnd,
+    /// `#`, prints the tape around the memory pointer to stderr.
+    Dump,
+    /// `(`, defines a procedure numbered by the current cell, without running it.
+    ProcedureStart,
+    /// `)`, returns from a procedure.
+    ProcedureEnd,
+    /// `:`, calls the procedure numbered by the current cell.
+    Call,
+}
+
+impl Instruction {
+    pub fn from_byte(byte: u8, dialect: &Dialect) -> Option<Instruction> {
+        match byte {
+            b'>' => Some(Instruction::MoveRight),
+            b'<' => Some(Instruction::MoveLeft),
+            b'+' => Some(Instruction::Increment),
+            b'-' => Some(Instruction::Decrement),
+            b'.' => Some(Instruction::Output),
+            b',' => Some(Instruction::Input),
+            b'[' => Some(Instruction::LoopStart),
+            b']' => Some(Instruction::LoopEnd),
+            b'#' if dialect.dump => Some(Instruction::Dump),
+            b'(' if dialect.procedures => Some(Instruction::ProcedureStart),
+            b')' if dialect.procedures => Some(Instruction::ProcedureEnd),
+            b':' if dialect.procedures => Some(Instruction::Call),
+            _ => None,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Instruction::MoveRight => b'>',
+            Instruction::MoveLeft => b'<',
+            Instruction::Increment => b'+',
+            Instruction::Decrement => b'-',
+            Instruction::Output => b'.',
+            Instruction::Input => b',',
+            Instruction::LoopStart => b'[',
+            Instruction::LoopEnd => b']',
+            Instruction::Dump => b'#',
+            Instruction::ProcedureStart => b'(',
+            Instruction::ProcedureEnd => b')',
+            Instruction::Call => b':',
+        }
+    }
+}
+
+/// Opt-in extensions to the eight standard instructions, which are comments otherwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Dialect {
+    /// `#` dumps the tape.
+    pub dump: bool,
+    /// Everything after the first `!` is input for the program, read before stdin.
+    pub embedded_input: bool,
+    /// pbrain procedures with `(`, `)` and `:`.
+    pub procedures: bool,
+}
+
+/// A line and column in brainfuck source, both starting at 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn from_offset(source: &[u8], offset: usize) -> Location {
+        let preceding: &[u8] = &source[..offset];
+        let line_start: usize = preceding
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0usize, |newline| newline + 1usize);
+
+        Location {
+            offset,
+            line: preceding.iter().filter(|&&byte| byte == b'\n').count() + 1usize,
+            column: offset - line_start + 1usize,
+        }
+    }
+
+    /// The source line containing the location, with a caret under its column.
+    pub fn snippet(&self, source: &[u8]) -> String {
+        let line_start: usize = self.offset + 1usize - self.column;
+        let line: &[u8] = source[line_start..]
+            .split(|&byte| byte == b'\n')
+            .next()
+            .unwrap_or_default();
+        let line: &[u8] = line.strip_suffix(b"\r").unwrap_or(line);
+        // Keep tabs so the caret lines up with the source line however the terminal renders them.
+        let padding: String = line[..self.column - 1usize]
+            .iter()
+            .map(|&byte| if byte == b'\t' { '\t' } else { ' ' })
+            .collect();
+        let gutter: String = " ".repeat(self.line.to_string().len());
+
+        format!(
+            "{gutter} |\n{} | {}\n{gutter} | {}^",
+            self.line,
+            String::from_utf8_lossy(line),
+            padding,
+        )
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnmatchedLoopStart(Location),
+    UnmatchedLoopEnd(Location),
+    UnmatchedProcedureStart(Location),
+    UnmatchedProcedureEnd(Location),
+}
+
+impl ParseError {
+    pub fn location(&self) -> Location {
+        match *self {
+            ParseError::UnmatchedLoopStart(location)
+            | ParseError::UnmatchedLoopEnd(location)
+            | ParseError::UnmatchedProcedureStart(location)
+            | ParseError::UnmatchedProcedureEnd(location) => location,
+        }
+    }
+
+    /// Renders the error as a diagnostic pointing at the offending bracket in `source`.
+    pub fn render(&self, path: &str, source: &[u8]) -> String {
+        let location: Location = self.location();
+        let gutter: String = " ".repeat(location.line.to_string().len());
+
+        format!(
+            "error: {}\n{gutter}--> {}:{}\n{}",
+            self,
+            path,
+            location,
+            location.snippet(source),
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnmatchedLoopStart(_) => write!(f, "unmatched `[`, loop is never closed"),
+            ParseError::UnmatchedLoopEnd(_) => write!(f, "unmatched `]`, no loop to close"),
+            ParseError::UnmatchedProcedureStart(_) => {
+                write!(f, "unmatched `(`, procedure is never closed")
+            }
+            ParseError::UnmatchedProcedureEnd(_) => {
+                write!(f, "unmatched `)`, no procedure to close")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A parsed brainfuck program with comments stripped and brackets matched.
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+    /// Byte offset in the source of each instruction.
+    pub offsets: Vec<usize>,
+    /// Index of the matching bracket or parenthesis for loop and procedure instructions, and of
+    /// the instruction itself otherwise.
+    pub jump_table: Vec<usize>,
+    /// Input embedded in the source after `!`, which the program reads before stdin.
+    pub input: Vec<u8>,
+}
+
+impl Program {
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+}
+
+/// Splits the source into code and the input embedded after the first `!`, if the dialect
+/// allows it.
+pub fn split_input<'a>(source: &'a [u8], dialect: &Dialect) -> (&'a [u8], Option<&'a [u8]>) {
+    match source.iter().position(|&byte| byte == b'!') {
+        Some(separator) if dialect.embedded_input => {
+            (&source[..separator], Some(&source[separator + 1usize..]))
+        }
+        _ => (source, None),
+    }
+}
+
+pub fn parse(source: &[u8], dialect: &Dialect) -> Result<Program, ParseError> {
+    let (code, input): (&[u8], Option<&[u8]>) = split_input(source, dialect);
+
+    let mut instructions: Vec<Instruction> = Vec::with_capacity(code.len());
+    let mut offsets: Vec<usize> = Vec::with_capacity(code.len());
+
+    for (offset, &byte) in code.iter().enumerate() {
+        if let Some(instruction) = Instruction::from_byte(byte, dialect) {
+            instructions.push(instruction);
+            offsets.push(offset);
+        }
+    }
+
+    let unmatched = |index: usize| -> ParseError {
+        let location: Location = Location::from_offset(source, offsets[index]);
+        match instructions[index] {
+            Instruction::LoopStart => ParseError::UnmatchedLoopStart(location),
+            Instruction::LoopEnd => ParseError::UnmatchedLoopEnd(location),
+            Instruction::ProcedureStart => ParseError::UnmatchedProcedureStart(location),
+            _ => ParseError::UnmatchedProcedureEnd(location),
+        }
+    };
+
+    // Loops and procedures share a stack, so that they can only nest and never overlap.
+    let mut stack: Vec<usize> = Vec::new();
+    let mut jump_table: Vec<usize> = (0..instructions.len()).collect();
+
+    for (i, instruction) in instructions.iter().enumerate() {
+        let opening: Instruction = match instruction {
+            Instruction::LoopStart | Instruction::ProcedureStart => {
+                stack.push(i);
+                continue;
+            }
+            Instruction::LoopEnd => Instruction::LoopStart,
+            Instruction::ProcedureEnd => Instruction::ProcedureStart,
+            _ => continue,
+        };
+
+        match stack.pop() {
+            Some(opening_index) if instructions[opening_index] == opening => {
+                jump_table[opening_index] = i;
+                jump_table[i] = opening_index;
+            }
+            // The innermost open loop or procedure is of the other kind, so it is never closed.
+            Some(opening_index) => return Err(unmatched(opening_index)),
+            None => return Err(unmatched(i)),
+        }
+    }
+
+    if let Some(&unmatched_index) = stack.last() {
+        return Err(unmatched(unmatched_index));
+    }
+
+    Ok(Program {
+        instructions,
+        offsets,
+        jump_table,
+        input: input.unwrap_or_default().to_vec(),
+    })
+}
@@ -0,0 +1,82 @@
+use std::io::{BufRead, Write};
+
+use crate::frontend::{Instruction, Program};
+use crate::interpreters::{self, Procedures, RuntimeError};
+use crate::io::{Console, Eof};
+use crate::limits::{Limits, Watchdog};
+use crate::tape::{Cell, Tape};
+
+/// Executes the instruction at `instruction_pointer` and returns the index of the next one.
+#[inline(always)]
+pub fn step<C: Cell, R: BufRead, W: Write>(
+    program: &Program,
+    tape: &mut Tape<C>,
+    console: &mut Console<R, W>,
+    procedures: &mut Procedures,
+    eof: Eof,
+    instruction_pointer: usize,
+) -> Result<usize, RuntimeError> {
+    match program.instructions[instruction_pointer] {
+        Instruction::MoveRight => tape.shift(1)?,
+        Instruction::MoveLeft => tape.shift(-1)?,
+        Instruction::Increment => tape.set_current(tape.current().wrapping_add(C::from_i32(1))),
+        Instruction::Decrement => tape.set_current(tape.current().wrapping_add(C::from_i32(-1))),
+        Instruction::Output => console.write_byte(tape.current().to_byte())?,
+        Instruction::Input => match console.read_byte()? {
+            Some(byte) => tape.set_current(C::from_byte(byte)),
+            None => tape.set_current(eof.apply(tape.current())),
+        },
+        Instruction::LoopStart if tape.current() == C::ZERO => {
+            return Ok(program.jump_table[instruction_pointer] + 1usize);
+        }
+        Instruction::LoopEnd if tape.current() != C::ZERO => {
+            return Ok(program.jump_table[instruction_pointer] + 1usize);
+        }
+        Instruction::LoopStart | Instruction::LoopEnd => {}
+        Instruction::Dump => interpreters::dump(tape, console)?,
+        Instruction::ProcedureStart => {
+            procedures.define(tape.current().to_usize(), instruction_pointer + 1usize);
+            return Ok(program.jump_table[instruction_pointer] + 1usize);
+        }
+        Instruction::ProcedureEnd => return Ok(procedures.return_address()),
+        Instruction::Call => {
+            return procedures.call(tape.current().to_usize(), instruction_pointer + 1usize);
+        }
+    }
+
+    Ok(instruction_pointer + 1usize)
+}
+
+/// Runs the program on `tape` with I/O through `console`, which is left unflushed, and returns
+/// the number of instructions executed.
+pub fn execute<C: Cell, R: BufRead, W: Write>(
+    program: &Program,
+    tape: &mut Tape<C>,
+    console: &mut Console<R, W>,
+    eof: Eof,
+    limits: &Limits,
+) -> Result<u64, RuntimeError> {
+    let mut instruction_pointer: usize = 0usize;
+    let mut procedures: Procedures = Procedures::default();
+    let mut watchdog: Watchdog = Watchdog::new(limits);
+
+    while instruction_pointer < program.len() {
+        if let Err(limit) = watchdog.tick() {
+            return Err(RuntimeError::LimitReached {
+                limit,
+                offset: program.offsets[instruction_pointer],
+                pointer: tape.pointer(),
+            });
+        }
+        instruction_pointer = step(
+            program,
+            tape,
+            console,
+            &mut procedures,
+            eof,
+            instruction_pointer,
+        )?;
+    }
+
+    Ok(watchdog.steps())
+}
@@ -0,0 +1,360 @@
+use std::io::{self, BufRead, Write};
+use std::ptr;
+
+use crate::frontend::Program;
+use crate::interpreters::RuntimeError;
+use crate::io::{Console, Eof};
+use crate::ir::{self, Op};
+use crate::tape::{self, Bounds, TapeConfig, TapeError};
+
+/// Marks a normal exit in `JitExit::failed_op`.
+const NO_FAILURE: usize = usize::MAX;
+/// Marks an exit caused by an I/O callback failing, whose error is left in `JitContext::error`.
+const IO_FAILURE: usize = usize::MAX - 1usize;
+
+/// State shared with the I/O callbacks of the generated code.
+struct JitContext<'a, R: BufRead, W: Write> {
+    console: &'a mut Console<R, W>,
+    eof: Eof,
+    error: Option<io::Error>,
+}
+
+/// Returned in RAX:RDX by the generated code.
+#[repr(C)]
+struct JitExit {
+    pointer: usize,
+    /// Index of the op whose memory access left the tape, or `NO_FAILURE`.
+    failed_op: usize,
+}
+
+type JitFunction<R, W> =
+    unsafe extern "sysv64" fn(*mut u8, *mut JitContext<R, W>, usize) -> JitExit;
+
+/// Writes the current cell. Returns whether it failed, as the generated code cannot unwind.
+extern "sysv64" fn write_callback<R: BufRead, W: Write>(
+    context: *mut JitContext<R, W>,
+    cell: *mut u8,
+) -> bool {
+    let context: &mut JitContext<R, W> = unsafe { &mut *context };
+    match context.console.write_byte(unsafe { *cell }) {
+        Ok(()) => false,
+        Err(error) => {
+            context.error = Some(error);
+            true
+        }
+    }
+}
+
+/// Reads into the current cell. Returns whether it failed, as the generated code cannot unwind.
+extern "sysv64" fn read_callback<R: BufRead, W: Write>(
+    context: *mut JitContext<R, W>,
+    cell: *mut u8,
+) -> bool {
+    let context: &mut JitContext<R, W> = unsafe { &mut *context };
+    match context.console.read_byte() {
+        Ok(byte) => {
+            unsafe { *cell = byte.unwrap_or_else(|| context.eof.apply(*cell)) };
+            false
+        }
+        Err(error) => {
+            context.error = Some(error);
+            true
+        }
+    }
+}
+
+/// Emits x86-64 machine code for the IR.
+///
+/// Register allocation: `rbx` holds the tape start, `r12` the memory pointer as an address, `r13`
+/// the `JitContext` and `r14` the tape length. All of them are callee-saved, so they survive the
+/// I/O callbacks.
+struct Assembler {
+    code: Vec<u8>,
+    /// Positions of `jae` displacements to patch with the error stub of an op.
+    bounds_checks: Vec<(usize, usize)>,
+    /// Positions of `jnz` displacements to patch with the I/O error stub.
+    io_checks: Vec<usize>,
+}
+
+impl Assembler {
+    fn new() -> Assembler {
+        Assembler {
+            code: Vec::new(),
+            bounds_checks: Vec::new(),
+            io_checks: Vec::new(),
+        }
+    }
+
+    fn emit(&mut self, bytes: &[u8]) {
+        self.code.extend_from_slice(bytes);
+    }
+
+    fn emit_u32(&mut self, value: u32) {
+        self.code.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Emits a 32-bit displacement placeholder and returns its position.
+    fn emit_displacement(&mut self) -> usize {
+        self.emit_u32(0u32);
+        self.code.len() - 4usize
+    }
+
+    /// Points the displacement at `position` to `target`.
+    fn patch(&mut self, position: usize, target: usize) {
+        let displacement: i32 = (target as isize - (position as isize + 4isize)) as i32;
+        self.code[position..position + 4usize].copy_from_slice(&displacement.to_le_bytes());
+    }
+
+    fn prologue(&mut self) {
+        self.emit(&[0x53]); // push rbx
+        self.emit(&[0x41, 0x54]); // push r12
+        self.emit(&[0x41, 0x55]); // push r13
+        self.emit(&[0x41, 0x56]); // push r14
+        self.emit(&[0x41, 0x57]); // push r15, keeps the stack 16-byte aligned for calls
+        self.emit(&[0x48, 0x89, 0xFB]); // mov rbx, rdi
+        self.emit(&[0x49, 0x89, 0xFC]); // mov r12, rdi
+        self.emit(&[0x49, 0x89, 0xF5]); // mov r13, rsi
+        self.emit(&[0x49, 0x89, 0xD6]); // mov r14, rdx
+    }
+
+    /// Loads the address `offset` cells from the pointer into `rcx`, leaving the tape through the
+    /// error stub of `op_index` if it is outside of it.
+    fn checked_address(&mut self, offset: isize, op_index: usize) {
+        self.emit(&[0x49, 0x8D, 0x8C, 0x24]); // lea rcx, [r12 + offset]
+        self.emit_u32(offset as i32 as u32);
+        self.emit(&[0x48, 0x89, 0xCA]); // mov rdx, rcx
+        self.emit(&[0x48, 0x29, 0xDA]); // sub rdx, rbx
+        self.emit(&[0x4C, 0x39, 0xF2]); // cmp rdx, r14
+        self.emit(&[0x0F, 0x83]); // jae error stub
+        let position: usize = self.emit_displacement();
+        self.bounds_checks.push((position, op_index));
+    }
+
+    fn shift(&mut self, distance: isize, op_index: usize) {
+        self.checked_address(distance, op_index);
+        self.emit(&[0x49, 0x89, 0xCC]); // mov r12, rcx
+    }
+
+    fn compare_cell_with_zero(&mut self) {
+        self.emit(&[0x41, 0x80, 0x3C, 0x24, 0x00]); // cmp byte [r12], 0
+    }
+
+    /// Calls an I/O callback with the context and the address of the current cell, leaving
+    /// through the I/O error stub if it fails.
+    fn call(&mut self, function: usize) {
+        self.emit(&[0x4C, 0x89, 0xEF]); // mov rdi, r13
+        self.emit(&[0x4C, 0x89, 0xE6]); // mov rsi, r12
+        self.emit(&[0x48, 0xB8]); // mov rax, function
+        self.code
+            .extend_from_slice(&(function as u64).to_le_bytes());
+        self.emit(&[0xFF, 0xD0]); // call rax
+        self.emit(&[0x84, 0xC0]); // test al, al
+        self.emit(&[0x0F, 0x85]); // jnz I/O error stub
+        let position: usize = self.emit_displacement();
+        self.io_checks.push(position);
+    }
+
+    fn epilogue(&mut self) {
+        self.emit(&[0x4C, 0x89, 0xE0]); // mov rax, r12
+        self.emit(&[0x48, 0x29, 0xD8]); // sub rax, rbx
+        self.emit(&[0x41, 0x5F]); // pop r15
+        self.emit(&[0x41, 0x5E]); // pop r14
+        self.emit(&[0x41, 0x5D]); // pop r13
+        self.emit(&[0x41, 0x5C]); // pop r12
+        self.emit(&[0x5B]); // pop rbx
+        self.emit(&[0xC3]); // ret
+    }
+
+    /// Assembles the ops, with I/O through the callbacks for a console of `R` and `W`.
+    fn assemble<R: BufRead, W: Write>(mut self, ops: &[Op]) -> Vec<u8> {
+        // Positions of the displacements of each loop's forward jump.
+        let mut loop_starts: Vec<usize> = Vec::new();
+
+        self.prologue();
+
+        for (op_index, &op) in ops.iter().enumerate() {
+            match op {
+                Op::Add(amount) => {
+                    self.emit(&[0x41, 0x80, 0x04, 0x24, amount as u8]); // add byte [r12], amount
+                }
+                Op::Move(distance) => self.shift(distance, op_index),
+                Op::SetZero => self.emit(&[0x41, 0xC6, 0x04, 0x24, 0x00]), // mov byte [r12], 0
+                Op::MulAdd { offset, factor } => {
+                    self.emit(&[0x41, 0x0F, 0xB6, 0x04, 0x24]); // movzx eax, byte [r12]
+                    self.emit(&[0x84, 0xC0]); // test al, al
+                    self.emit(&[0x0F, 0x84]); // jz skip
+                    let skip: usize = self.emit_displacement();
+                    self.checked_address(offset, op_index);
+                    self.emit(&[0x69, 0xC0]); // imul eax, eax, factor
+                    self.emit_u32(factor as u32);
+                    self.emit(&[0x00, 0x01]); // add byte [rcx], al
+                    let target: usize = self.code.len();
+                    self.patch(skip, target);
+                }
+                Op::ScanLeft(stride) | Op::ScanRight(stride) => {
+                    let distance: isize = if let Op::ScanLeft(_) = op {
+                        -(stride as isize)
+                    } else {
+                        stride as isize
+                    };
+                    let start: usize = self.code.len();
+                    self.compare_cell_with_zero();
+                    self.emit(&[0x0F, 0x84]); // je done
+                    let done: usize = self.emit_displacement();
+                    self.shift(distance, op_index);
+                    self.emit(&[0xE9]); // jmp start
+                    let back: usize = self.emit_displacement();
+                    self.patch(back, start);
+                    let target: usize = self.code.len();
+                    self.patch(done, target);
+                }
+                Op::Output => self.call(write_callback::<R, W> as *const () as usize),
+                Op::Input => self.call(read_callback::<R, W> as *const () as usize),
+                Op::LoopStart(_) => {
+                    self.compare_cell_with_zero();
+                    self.emit(&[0x0F, 0x84]); // je past the loop end
+                    loop_starts.push(self.emit_displacement());
+                }
+                Op::LoopEnd(_) => {
+                    let forward: usize = loop_starts.pop().unwrap();
+                    self.compare_cell_with_zero();
+                    self.emit(&[0x0F, 0x85]); // jne past the loop start
+                    let backward: usize = self.emit_displacement();
+                    self.patch(backward, forward + 4usize);
+                    let target: usize = self.code.len();
+                    self.patch(forward, target);
+                }
+                op => unreachable!("{:?} is rejected before assembly", op),
+            }
+        }
+
+        self.emit(&[0x48, 0xC7, 0xC2, 0xFF, 0xFF, 0xFF, 0xFF]); // mov rdx, NO_FAILURE
+        let exit: usize = self.code.len();
+        self.epilogue();
+
+        for (position, op_index) in std::mem::take(&mut self.bounds_checks) {
+            let stub: usize = self.code.len();
+            self.patch(position, stub);
+            self.emit(&[0xBA]); // mov edx, op_index
+            self.emit_u32(op_index as u32);
+            self.emit(&[0xE9]); // jmp exit
+            let displacement: usize = self.emit_displacement();
+            self.patch(displacement, exit);
+        }
+
+        let io_stub: usize = self.code.len();
+        for position in std::mem::take(&mut self.io_checks) {
+            self.patch(position, io_stub);
+        }
+        self.emit(&[0x48, 0xC7, 0xC2, 0xFE, 0xFF, 0xFF, 0xFF]); // mov rdx, IO_FAILURE
+        self.emit(&[0xE9]); // jmp exit
+        let displacement: usize = self.emit_displacement();
+        self.patch(displacement, exit);
+
+        self.code
+    }
+}
+
+/// A page-aligned mapping holding generated code, executable but no longer writable.
+struct ExecutableMemory {
+    address: *mut libc::c_void,
+    length: usize,
+}
+
+impl ExecutableMemory {
+    fn new(code: &[u8]) -> io::Result<ExecutableMemory> {
+        let length: usize = code.len().max(1usize);
+
+        unsafe {
+            let address: *mut libc::c_void = libc::mmap(
+                ptr::null_mut(),
+                length,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            );
+            if address == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+
+            let memory: ExecutableMemory = ExecutableMemory { address, length };
+            ptr::copy_nonoverlapping(code.as_ptr(), address as *mut u8, code.len());
+            if libc::mprotect(address, length, libc::PROT_READ | libc::PROT_EXEC) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(memory)
+        }
+    }
+}
+
+impl Drop for ExecutableMemory {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.address, self.length);
+        }
+    }
+}
+
+/// Compiles and runs the program with I/O through `console`, which is left unflushed.
+pub fn execute<R: BufRead, W: Write>(
+    program: &Program,
+    tape_config: &TapeConfig,
+    console: &mut Console<R, W>,
+    eof: Eof,
+) -> Result<(), RuntimeError> {
+    if tape_config.growable || tape_config.bounds == Bounds::Wrap {
+        return Err(RuntimeError::Unsupported(
+            "the JIT only supports fixed-size tapes that stop at their bounds",
+        ));
+    }
+
+    if program.is_empty() {
+        return Ok(());
+    }
+
+    let ops: Vec<Op> = ir::build(program).ops;
+    if ops.iter().any(Op::is_extension) {
+        return Err(RuntimeError::Unsupported(
+            "the JIT does not support dialect extensions",
+        ));
+    }
+    let memory: ExecutableMemory = ExecutableMemory::new(&Assembler::new().assemble::<R, W>(&ops))?;
+    let function: JitFunction<R, W> = unsafe { std::mem::transmute(memory.address) };
+
+    let mut tape: Vec<u8> = tape::zeroed(tape_config.length.min(tape_config.max_length))?;
+    let mut context: JitContext<R, W> = JitContext {
+        console,
+        eof,
+        error: None,
+    };
+
+    let exit: JitExit = unsafe { function(tape.as_mut_ptr(), &mut context, tape.len()) };
+
+    match exit.failed_op {
+        NO_FAILURE => return Ok(()),
+        IO_FAILURE => return Err(RuntimeError::Io(context.error.take().unwrap())),
+        _ => {}
+    }
+    let distance: isize = match ops[exit.failed_op] {
+        Op::Move(distance) => distance,
+        Op::MulAdd { offset, .. } => offset,
+        Op::ScanLeft(stride) => -(stride as isize),
+        Op::ScanRight(stride) => stride as isize,
+        op => unreachable!("{:?} does not access other cells", op),
+    };
+
+    Err(RuntimeError::Tape(if distance < 0 {
+        TapeError::Underflow {
+            pointer: exit.pointer,
+            distance: distance.unsigned_abs(),
+        }
+    } else {
+        TapeError::Overflow {
+            pointer: exit.pointer,
+            distance: distance as usize,
+            length: tape.len(),
+        }
+    }))
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, Write};
+use std::mem::size_of;
+
+use crate::frontend::{Location, Program};
+use crate::io::{Console, IoConfig};
+use crate::ir::{self, Ir};
+use crate::limits::{Limit, Limits};
+use crate::tape::{Cell, Tape, TapeConfig, TapeError};
+
+/// Cells printed on each side of the memory pointer by `#`.
+const DUMP_RADIUS: usize = 8usize;
+
+pub mod basic;
+#[cfg(all(target_arch = "x86_64", unix))]
+pub mod jit;
+pub mod optimized;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// One instruction of the source at a time.
+    Basic,
+    /// The IR, with runs of instructions merged and common loops folded.
+    Optimized,
+    /// The IR compiled to x86-64 machine code, for 8-bit cells without limits only.
+    Jit,
+}
+
+#[derive(Debug)]
+pub enum RuntimeError {
+    Tape(TapeError),
+    Io(io::Error),
+    Unsupported(&'static str),
+    /// `:` called a procedure that no `(` has defined.
+    UndefinedProcedure(usize),
+    /// The program was stopped before the instruction at `offset` in the source, with the memory
+    /// pointer at cell `pointer`.
+    LimitReached {
+        limit: Limit,
+        offset: usize,
+        pointer: usize,
+    },
+}
+
+impl RuntimeError {
+    /// Renders the error, pointing at where the program was in `source` if it was stopped.
+    pub fn render(&self, path: &str, source: &[u8]) -> String {
+        match *self {
+            RuntimeError::LimitReached {
+                offset, pointer, ..
+            } => {
+                let location: Location = Location::from_offset(source, offset);
+                let gutter: String = " ".repeat(location.line.to_string().len());
+                format!(
+                    "error: {}\n{gutter}--> {}:{}\n{}\n{gutter} = memory pointer at cell {}",
+                    self,
+                    path,
+                    location,
+                    location.snippet(source),
+                    pointer,
+                )
+            }
+            _ => format!("error: {}", self),
+        }
+    }
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::Tape(error) => write!(f, "{}", error),
+            RuntimeError::Io(error) => write!(f, "{}", error),
+            RuntimeError::Unsupported(reason) => write!(f, "{}", reason),
+            RuntimeError::UndefinedProcedure(id) => write!(f, "procedure {} is not defined", id),
+            RuntimeError::LimitReached { limit, .. } => write!(f, "{}", limit),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+impl From<TapeError> for RuntimeError {
+    fn from(error: TapeError) -> RuntimeError {
+        RuntimeError::Tape(error)
+    }
+}
+
+impl From<io::Error> for RuntimeError {
+    fn from(error: io::Error) -> RuntimeError {
+        RuntimeError::Io(error)
+    }
+}
+
+/// The pbrain procedures defined so far, and the return addresses of the calls in progress.
+#[derive(Default)]
+pub struct Procedures {
+    bodies: HashMap<usize, usize>,
+    returns: Vec<usize>,
+}
+
+impl Procedures {
+    pub fn define(&mut self, id: usize, body: usize) {
+        self.bodies.insert(id, body);
+    }
+
+    /// Enters procedure `id` and returns the index of its first instruction.
+    pub fn call(&mut self, id: usize, return_address: usize) -> Result<usize, RuntimeError> {
+        let &body: &usize = self
+            .bodies
+            .get(&id)
+            .ok_or(RuntimeError::UndefinedProcedure(id))?;
+        self.returns.push(return_address);
+        Ok(body)
+    }
+
+    /// Leaves the innermost procedure and returns the index to continue from. Definitions jump
+    /// over the procedure body, so `)` is only ever reached from inside a call.
+    pub fn return_address(&mut self) -> usize {
+        self.returns.pop().unwrap()
+    }
+}
+
+/// Runs `#`, printing the tape to stderr after the output so far.
+pub fn dump<C: Cell, R: BufRead, W: Write>(
+    tape: &Tape<C>,
+    console: &mut Console<R, W>,
+) -> io::Result<()> {
+    console.flush()?;
+    eprintln!("{}", tape.window(DUMP_RADIUS));
+    Ok(())
+}
+
+/// Runs the program in `mode` with `input` for `,` and output to stdout.
+pub fn run<C: Cell, R: BufRead>(
+    mode: Mode,
+    program: &Program,
+    tape_config: &TapeConfig,
+    io_config: &IoConfig,
+    limits: &Limits,
+    input: R,
+) -> Result<(), RuntimeError> {
+    let mut console: Console<R> = Console::with_reader(input, io_config);
+    console.embed_input(&program.input);
+
+    match mode {
+        Mode::Basic => {
+            let mut tape: Tape<C> = Tape::new(tape_config)?;
+            basic::execute(program, &mut tape, &mut console, io_config.eof, limits)?;
+        }
+        Mode::Optimized => {
+            let ir: Ir = ir::build_for_tape(program, tape_config);
+            let mut tape: Tape<C> = Tape::new(tape_config)?;
+            optimized::execute::<C, _, _, false>(
+                &ir,
+                &mut tape,
+                &mut console,
+                io_config.eof,
+                limits,
+                &mut [],
+            )?;
+        }
+        Mode::Jit if size_of::<C>() != 1usize || !cfg!(all(target_arch = "x86_64", unix)) => {
+            return Err(RuntimeError::Unsupported(
+                "the JIT only supports 8-bit cells on x86-64 unix",
+            ));
+        }
+        Mode::Jit if !limits.is_unlimited() => {
+            return Err(RuntimeError::Unsupported(
+                "the JIT does not support step or time limits",
+            ));
+        }
+        Mode::Jit => {
+            #[cfg(all(target_arch = "x86_64", unix))]
+            jit::execute(program, tape_config, &mut console, io_config.eof)?;
+        }
+    }
+
+    Ok(console.finish()?)
+}
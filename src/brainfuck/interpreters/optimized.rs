@@ -0,0 +1,137 @@
+use std::io::{BufRead, Write};
+
+use crate::frontend::Program;
+use crate::interpreters::{self, Procedures, RuntimeError};
+use crate::io::{Console, Eof, IoConfig};
+use crate::ir::{self, Ir, Op};
+use crate::limits::{Limits, Watchdog};
+use crate::profile::Profile;
+use crate::tape::{Cell, Tape, TapeConfig};
+
+/// Takes a step of the op at `instruction_pointer`, stopping the program there if it ran into a
+/// limit, and counting the step in `counts` if `PROFILE` is set.
+#[inline(always)]
+fn tick<C: Cell, const PROFILE: bool>(
+    watchdog: &mut Watchdog,
+    ir: &Ir,
+    instruction_pointer: usize,
+    tape: &Tape<C>,
+    counts: &mut [u64],
+) -> Result<(), RuntimeError> {
+    if PROFILE {
+        counts[instruction_pointer] += 1u64;
+    }
+    watchdog.tick().map_err(|limit| RuntimeError::LimitReached {
+        limit,
+        offset: ir.offsets[instruction_pointer],
+        pointer: tape.pointer(),
+    })
+}
+
+/// Runs the ops and returns how many steps they took, also counting the steps of each op in
+/// `counts` if `PROFILE` is set. The counting compiles away entirely otherwise.
+#[inline(always)]
+pub fn execute<C: Cell, R: BufRead, W: Write, const PROFILE: bool>(
+    ir: &Ir,
+    tape: &mut Tape<C>,
+    console: &mut Console<R, W>,
+    eof: Eof,
+    limits: &Limits,
+    counts: &mut [u64],
+) -> Result<u64, RuntimeError> {
+    let ops: &[Op] = &ir.ops;
+    let mut instruction_pointer: usize = 0usize;
+    let mut procedures: Procedures = Procedures::default();
+    let mut watchdog: Watchdog = Watchdog::new(limits);
+
+    while let Some(&op) = ops.get(instruction_pointer) {
+        tick::<C, PROFILE>(&mut watchdog, ir, instruction_pointer, tape, counts)?;
+        match op {
+            Op::Add(amount) => tape.set_current(tape.current().wrapping_add(C::from_i32(amount))),
+            Op::Move(distance) => tape.shift(distance)?,
+            Op::SetZero => tape.set_current(C::ZERO),
+            Op::MulAdd { offset, factor } => {
+                let value: C = tape.current();
+                if value != C::ZERO {
+                    tape.add_at(offset, value.wrapping_mul(C::from_i32(factor)))?;
+                }
+            }
+            // A scan never ends on a wrapping tape without a zero cell, so every cell it moves
+            // past is a step of its own.
+            Op::ScanLeft(stride) => {
+                while tape.current() != C::ZERO {
+                    tick::<C, PROFILE>(&mut watchdog, ir, instruction_pointer, tape, counts)?;
+                    tape.shift(-(stride as isize))?;
+                }
+            }
+            Op::ScanRight(stride) => {
+                while tape.current() != C::ZERO {
+                    tick::<C, PROFILE>(&mut watchdog, ir, instruction_pointer, tape, counts)?;
+                    tape.shift(stride as isize)?;
+                }
+            }
+            Op::Output => console.write_byte(tape.current().to_byte())?,
+            Op::Input => match console.read_byte()? {
+                Some(byte) => tape.set_current(C::from_byte(byte)),
+                None => tape.set_current(eof.apply(tape.current())),
+            },
+            Op::LoopStart(end) => {
+                if tape.current() == C::ZERO {
+                    instruction_pointer = end;
+                }
+            }
+            Op::LoopEnd(start) => {
+                if tape.current() != C::ZERO {
+                    instruction_pointer = start;
+                }
+            }
+            Op::Dump => interpreters::dump(tape, console)?,
+            Op::ProcedureStart(end) => {
+                procedures.define(tape.current().to_usize(), instruction_pointer + 1usize);
+                instruction_pointer = end;
+            }
+            Op::ProcedureEnd => {
+                instruction_pointer = procedures.return_address();
+                continue;
+            }
+            Op::Call => {
+                instruction_pointer =
+                    procedures.call(tape.current().to_usize(), instruction_pointer + 1usize)?;
+                continue;
+            }
+        }
+        instruction_pointer += 1;
+    }
+
+    Ok(watchdog.steps())
+}
+
+/// Runs the program like `interpreters::run`, and also returns the steps each op took, even if
+/// the program stopped with an error.
+pub fn profile<C: Cell, R: BufRead>(
+    program: &Program,
+    tape_config: &TapeConfig,
+    io_config: &IoConfig,
+    limits: &Limits,
+    input: R,
+) -> (Profile, Result<(), RuntimeError>) {
+    let ir: Ir = ir::build_for_tape(program, tape_config);
+    let mut counts: Vec<u64> = vec![0u64; ir.ops.len()];
+    let mut console: Console<R> = Console::with_reader(input, io_config);
+    console.embed_input(&program.input);
+    let result: Result<(), RuntimeError> = Tape::new(tape_config)
+        .map_err(RuntimeError::from)
+        .and_then(|mut tape: Tape<C>| {
+            execute::<C, _, _, true>(
+                &ir,
+                &mut tape,
+                &mut console,
+                io_config.eof,
+                limits,
+                &mut counts,
+            )
+        })
+        .and_then(|_| Ok(console.finish()?));
+
+    (Profile { ir, counts }, result)
+}
@@ -0,0 +1,150 @@
+use std::io::{self, stdin, stdout, BufRead, BufWriter, StdinLock, StdoutLock, Write};
+
+use crate::tape::Cell;
+
+/// What `,` stores in the current cell once the input is exhausted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Eof {
+    Unchanged,
+    Zero,
+    /// -1, i.e. every bit of the cell set (255 for 8-bit cells).
+    MinusOne,
+}
+
+impl Eof {
+    pub fn apply<C: Cell>(self, cell: C) -> C {
+        match self {
+            Eof::Unchanged => cell,
+            Eof::Zero => C::ZERO,
+            Eof::MinusOne => C::from_i32(-1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IoMode {
+    /// Line-buffered input, and only ASCII output, flushed after every byte.
+    Text,
+    /// Every byte value passes through unchanged, and output is only flushed when the program
+    /// asks for input or exits.
+    Raw,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IoConfig {
+    pub mode: IoMode,
+    pub eof: Eof,
+}
+
+/// The input and output of a running brainfuck program, which are stdin and stdout unless
+/// created with `with_io`.
+pub struct Console<R: BufRead = StdinLock<'static>, W: Write = BufWriter<StdoutLock<'static>>> {
+    reader: R,
+    writer: W,
+    /// Input from the program source, read before anything from `reader`.
+    embedded_input: Vec<u8>,
+    embedded_position: usize,
+    line: Vec<u8>,
+    line_position: usize,
+    mode: IoMode,
+}
+
+impl Console {
+    pub fn new(config: &IoConfig) -> Console {
+        Console::with_reader(stdin().lock(), config)
+    }
+}
+
+impl<R: BufRead> Console<R> {
+    /// A console reading from `reader` and writing to stdout.
+    pub fn with_reader(reader: R, config: &IoConfig) -> Console<R> {
+        Console::with_io(reader, BufWriter::new(stdout().lock()), config)
+    }
+}
+
+impl<R: BufRead, W: Write> Console<R, W> {
+    pub fn with_io(reader: R, writer: W, config: &IoConfig) -> Console<R, W> {
+        Console {
+            reader,
+            writer,
+            embedded_input: Vec::new(),
+            embedded_position: 0usize,
+            line: Vec::new(),
+            line_position: 0usize,
+            mode: config.mode,
+        }
+    }
+
+    pub fn write_byte(&mut self, byte: u8) -> io::Result<()> {
+        match self.mode {
+            IoMode::Text if byte.is_ascii() => {
+                self.writer.write_all(&[byte])?;
+                self.writer.flush()
+            }
+            IoMode::Text => Ok(()),
+            IoMode::Raw => self.writer.write_all(&[byte]),
+        }
+    }
+
+    pub fn embed_input(&mut self, input: &[u8]) {
+        self.embedded_input = input.to_vec();
+        self.embedded_position = 0usize;
+    }
+
+    /// The next input byte, or `None` once the input is at EOF.
+    pub fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        if let Some(&byte) = self.embedded_input.get(self.embedded_position) {
+            self.embedded_position += 1usize;
+            return Ok(Some(byte));
+        }
+
+        match self.mode {
+            IoMode::Text => {
+                if self.line_position == self.line.len() {
+                    self.line.clear();
+                    self.line_position = 0usize;
+                    self.reader.read_until(b'\n', &mut self.line)?;
+                }
+
+                let Some(&byte) = self.line.get(self.line_position) else {
+                    return Ok(None);
+                };
+                self.line_position += 1usize;
+                Ok(Some(byte))
+            }
+            IoMode::Raw => {
+                self.writer.flush()?;
+
+                let Some(&byte) = self.reader.fill_buf()?.first() else {
+                    return Ok(None);
+                };
+                self.reader.consume(1usize);
+                Ok(Some(byte))
+            }
+        }
+    }
+
+    /// Reads a line of input that is not meant for the program, such as a debugger command.
+    /// Returns `None` once the input is at EOF.
+    pub fn read_line(&mut self) -> io::Result<Option<String>> {
+        self.writer.flush()?;
+
+        let mut line: String = String::new();
+        if self.reader.read_line(&mut line)? == 0usize {
+            return Ok(None);
+        }
+        Ok(Some(line))
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
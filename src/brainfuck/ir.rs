@@ -0,0 +1,248 @@
+use crate::frontend::{Instruction, Program};
+use crate::tape::{Bounds, TapeConfig};
+
+/// A single operation of the optimized intermediate representation.
+///
+/// Loop operations carry the index of their matching bracket so backends can jump directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Add(i32),
+    Move(isize),
+    SetZero,
+    MulAdd {
+        offset: isize,
+        factor: i32,
+    },
+    ScanLeft(usize),
+    ScanRight(usize),
+    Output,
+    Input,
+    LoopStart(usize),
+    LoopEnd(usize),
+    Dump,
+    /// Defines a procedure and jumps past the index of its end.
+    ProcedureStart(usize),
+    ProcedureEnd,
+    Call,
+}
+
+impl Op {
+    /// Whether the op comes from a dialect extension rather than standard brainfuck.
+    pub fn is_extension(&self) -> bool {
+        matches!(
+            self,
+            Op::Dump | Op::ProcedureStart(_) | Op::ProcedureEnd | Op::Call
+        )
+    }
+}
+
+/// An optimized program.
+pub struct Ir {
+    pub ops: Vec<Op>,
+    /// Source byte offset of the first instruction each op was built from. Ops folded from a loop
+    /// map to its opening bracket.
+    pub offsets: Vec<usize>,
+}
+
+/// Lowers a parsed program into run-length encoded operations, without resolving loop targets.
+fn lower(program: &Program) -> Ir {
+    let mut ops: Vec<Op> = Vec::with_capacity(program.len());
+    let mut offsets: Vec<usize> = Vec::with_capacity(program.len());
+
+    for (instruction, &source_offset) in program.instructions.iter().zip(&program.offsets) {
+        let op: Op = match instruction {
+            Instruction::Increment => Op::Add(1),
+            Instruction::Decrement => Op::Add(-1),
+            Instruction::MoveRight => Op::Move(1),
+            Instruction::MoveLeft => Op::Move(-1),
+            Instruction::Output => Op::Output,
+            Instruction::Input => Op::Input,
+            Instruction::LoopStart => Op::LoopStart(0usize),
+            Instruction::LoopEnd => Op::LoopEnd(0usize),
+            Instruction::Dump => Op::Dump,
+            Instruction::ProcedureStart => Op::ProcedureStart(0usize),
+            Instruction::ProcedureEnd => Op::ProcedureEnd,
+            Instruction::Call => Op::Call,
+        };
+
+        match (ops.last_mut(), op) {
+            (Some(Op::Add(total)), Op::Add(amount)) => *total += amount,
+            // Moves only merge in one direction, so that `><` still checks the cell it visits.
+            (Some(Op::Move(total)), Op::Move(distance)) if total.signum() == distance.signum() => {
+                *total += distance
+            }
+            _ => {
+                ops.push(op);
+                offsets.push(source_offset);
+            }
+        }
+
+        if let Some(Op::Add(0)) = ops.last() {
+            ops.pop();
+            offsets.pop();
+        }
+    }
+
+    Ir { ops, offsets }
+}
+
+/// Replaces an innermost loop body with an equivalent straight-line sequence, if it is a
+/// recognised idiom that stays less than `reach` cells from the cell it starts on.
+fn fold_loop(body: &[Op], reach: usize) -> Option<Vec<Op>> {
+    match body {
+        // `[-]` and `[+]` terminate for every cell width, so both clear the cell.
+        [Op::Add(amount)] if amount % 2 != 0 => return Some(vec![Op::SetZero]),
+        [Op::Move(distance)] if *distance < 0 => {
+            return Some(vec![Op::ScanLeft(distance.unsigned_abs())])
+        }
+        [Op::Move(distance)] => return Some(vec![Op::ScanRight(distance.unsigned_abs())]),
+        _ => {}
+    }
+
+    // Copy and multiply loops: only additions and moves, returning to the starting cell and
+    // decrementing it by exactly one per iteration.
+    let mut offset: isize = 0isize;
+    let mut lowest: isize = 0isize;
+    let mut highest: isize = 0isize;
+    let mut deltas: Vec<(isize, i32)> = Vec::new();
+    for op in body {
+        match *op {
+            Op::Add(amount) => match deltas.iter_mut().find(|(cell, _)| *cell == offset) {
+                Some((_, total)) => *total += amount,
+                None => deltas.push((offset, amount)),
+            },
+            Op::Move(distance) => {
+                offset += distance;
+                lowest = lowest.min(offset);
+                highest = highest.max(offset);
+            }
+            _ => return None,
+        }
+    }
+
+    if offset != 0 || deltas.iter().find(|(cell, _)| *cell == 0) != Some(&(0isize, -1)) {
+        return None;
+    }
+    // The loop moves through every cell between its furthest ones, and may fail or grow the tape
+    // there, so those must be added to for the checks of the folded ops to stand in for its moves.
+    let is_added_to = |extreme: isize| {
+        extreme == 0isize
+            || deltas
+                .iter()
+                .any(|&(cell, factor)| cell == extreme && factor != 0i32)
+    };
+    if !is_added_to(lowest)
+        || !is_added_to(highest)
+        || lowest.unsigned_abs() >= reach
+        || highest.unsigned_abs() >= reach
+    {
+        return None;
+    }
+
+    let mut folded: Vec<Op> = deltas
+        .into_iter()
+        .filter(|&(cell, factor)| cell != 0 && factor != 0)
+        .map(|(offset, factor)| Op::MulAdd { offset, factor })
+        .collect();
+    folded.push(Op::SetZero);
+
+    Some(folded)
+}
+
+/// Pass that folds recognised loop idioms reaching less than `reach` cells, and drops loops that
+/// can never be entered.
+fn fold_loops(ir: Ir, reach: usize) -> Ir {
+    let mut folded: Vec<Op> = Vec::with_capacity(ir.ops.len());
+    let mut folded_offsets: Vec<usize> = Vec::with_capacity(ir.ops.len());
+    let mut stack: Vec<usize> = Vec::new();
+    // Nesting depth of the outermost loop that is entered with a zero cell and therefore never runs.
+    let mut dead_loop: Option<usize> = None;
+
+    for (op, offset) in ir.ops.into_iter().zip(ir.offsets) {
+        match op {
+            Op::LoopStart(_) => {
+                if dead_loop.is_none()
+                    && matches!(
+                        folded.last(),
+                        None | Some(
+                            Op::LoopEnd(_) | Op::SetZero | Op::ScanLeft(_) | Op::ScanRight(_)
+                        )
+                    )
+                {
+                    dead_loop = Some(stack.len());
+                }
+                stack.push(folded.len());
+                folded.push(op);
+                folded_offsets.push(offset);
+            }
+            Op::LoopEnd(_) => {
+                let start: usize = stack.pop().unwrap();
+                if dead_loop == Some(stack.len()) {
+                    folded.truncate(start);
+                    folded_offsets.truncate(start);
+                    dead_loop = None;
+                } else if let Some(replacement) = fold_loop(&folded[start + 1usize..], reach) {
+                    let loop_offset: usize = folded_offsets[start];
+                    folded.truncate(start);
+                    folded_offsets.truncate(start);
+                    folded_offsets.resize(start + replacement.len(), loop_offset);
+                    folded.extend(replacement);
+                } else {
+                    folded.push(op);
+                    folded_offsets.push(offset);
+                }
+            }
+            _ => {
+                folded.push(op);
+                folded_offsets.push(offset);
+            }
+        }
+    }
+
+    Ir {
+        ops: folded,
+        offsets: folded_offsets,
+    }
+}
+
+/// Resolves the jump targets of every loop and procedure. Both are known to be balanced and
+/// properly nested by the front-end.
+fn link(ops: &mut [Op]) {
+    let mut stack: Vec<usize> = Vec::new();
+
+    for i in 0..ops.len() {
+        match ops[i] {
+            Op::LoopStart(_) | Op::ProcedureStart(_) => stack.push(i),
+            Op::LoopEnd(_) => {
+                let start: usize = stack.pop().unwrap();
+                ops[start] = Op::LoopStart(i);
+                ops[i] = Op::LoopEnd(start);
+            }
+            Op::ProcedureEnd => {
+                let start: usize = stack.pop().unwrap();
+                ops[start] = Op::ProcedureStart(i);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn optimize(program: &Program, reach: usize) -> Ir {
+    let mut ir: Ir = fold_loops(lower(program), reach);
+    link(&mut ir.ops);
+    ir
+}
+
+/// Builds the IR for a tape that stops or grows at its ends.
+pub fn build(program: &Program) -> Ir {
+    optimize(program, usize::MAX)
+}
+
+/// Builds the IR for a tape configured by `tape_config`. On a wrapping tape, loops that reach as
+/// far as its initial length are kept, as they may come around to the cell they count down.
+pub fn build_for_tape(program: &Program, tape_config: &TapeConfig) -> Ir {
+    match tape_config.bounds {
+        Bounds::Wrap => optimize(program, tape_config.initial_length()),
+        Bounds::Error => build(program),
+    }
+}
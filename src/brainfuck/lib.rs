@@ -0,0 +1,11 @@
+//! Parsing, optimizing and running brainfuck, for the `brainfuck` command line tool and for
+//! running programs from other code with `vm::Vm`.
+
+pub mod frontend;
+pub mod interpreters;
+pub mod io;
+pub mod ir;
+pub mod limits;
+pub mod profile;
+pub mod tape;
+pub mod vm;
@@ -0,0 +1,107 @@
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// Steps between two checks of the clock, so that a timeout costs next to nothing per step.
+const CLOCK_INTERVAL: u64 = 1u64 << 16;
+
+/// Bounds on how long a program may run. A step is one instruction in the basic interpreter and
+/// one op of the optimized program otherwise, or one move of a scan.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub max_steps: Option<u64>,
+    pub timeout: Option<Duration>,
+}
+
+impl Limits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_steps.is_none() && self.timeout.is_none()
+    }
+}
+
+/// The limit a program ran into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Steps(u64),
+    Time(Duration),
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::Steps(steps) => write!(f, "step limit of {} reached", steps),
+            Limit::Time(timeout) => write!(f, "time limit of {:?} reached", timeout),
+        }
+    }
+}
+
+/// Enforces `Limits` on a running program, which calls `tick` before every step.
+pub struct Watchdog {
+    limits: Limits,
+    started: Instant,
+    /// Steps taken before the current budget was handed out.
+    steps: u64,
+    budget: u64,
+    /// Steps left in the budget. The limits are only checked once it runs out.
+    remaining: u64,
+}
+
+impl Watchdog {
+    pub fn new(limits: &Limits) -> Watchdog {
+        let mut watchdog: Watchdog = Watchdog {
+            limits: *limits,
+            started: Instant::now(),
+            steps: 0u64,
+            budget: 0u64,
+            remaining: 0u64,
+        };
+        watchdog.budget = watchdog.next_budget();
+        watchdog.remaining = watchdog.budget;
+        watchdog
+    }
+
+    fn next_budget(&self) -> u64 {
+        let budget: u64 = self
+            .limits
+            .max_steps
+            .map_or(u64::MAX, |max_steps| max_steps - self.steps);
+        match self.limits.timeout {
+            Some(_) => budget.min(CLOCK_INTERVAL),
+            None => budget,
+        }
+    }
+
+    /// Steps taken so far.
+    pub fn steps(&self) -> u64 {
+        self.steps + (self.budget - self.remaining)
+    }
+
+    #[inline(always)]
+    pub fn tick(&mut self) -> Result<(), Limit> {
+        if self.remaining == 0u64 {
+            return self.check();
+        }
+        self.remaining -= 1u64;
+        Ok(())
+    }
+
+    #[cold]
+    fn check(&mut self) -> Result<(), Limit> {
+        self.steps += self.budget;
+
+        if let Some(max_steps) = self.limits.max_steps.filter(|&max| self.steps >= max) {
+            return Err(Limit::Steps(max_steps));
+        }
+        if let Some(timeout) = self
+            .limits
+            .timeout
+            .filter(|&timeout| self.started.elapsed() >= timeout)
+        {
+            return Err(Limit::Time(timeout));
+        }
+
+        self.budget = self.next_budget();
+        // This tick takes the first step of the new budget.
+        self.remaining = self.budget - 1u64;
+        Ok(())
+    }
+}
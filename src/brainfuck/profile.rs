@@ -0,0 +1,213 @@
+use std::fmt::Write as _;
+
+use crate::frontend::{self, Dialect, Instruction, Location};
+use crate::ir::{Ir, Op};
+
+/// Number of rows in each table of the report.
+const REPORT_ROWS: usize = 10usize;
+/// Longest loop body shown in the report, in instructions.
+const SNIPPET_LENGTH: usize = 40usize;
+
+/// The steps each op of an optimized program took, counted as `--max-steps` counts them: one
+/// whenever an op executes, and one more for every cell a scan moves past.
+pub struct Profile {
+    pub ir: Ir,
+    /// Steps of the op at the same index in `ir.ops`.
+    pub counts: Vec<u64>,
+}
+
+/// A loop that survived optimization, and the work done inside it.
+struct LoopProfile {
+    start: usize,
+    end: usize,
+    /// Times the loop was reached, whether or not its body ran.
+    entries: u64,
+    iterations: u64,
+    /// Steps from the opening to the closing bracket, including nested loops.
+    steps: u64,
+}
+
+impl Profile {
+    fn total_steps(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    fn loops(&self) -> Vec<LoopProfile> {
+        self.ir
+            .ops
+            .iter()
+            .enumerate()
+            .filter_map(|(start, op)| match *op {
+                Op::LoopStart(end) => Some(LoopProfile {
+                    start,
+                    end,
+                    entries: self.counts[start],
+                    // The closing bracket runs once at the end of every pass through the body.
+                    iterations: self.counts[end],
+                    steps: self.counts[start..=end].iter().sum(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The brainfuck instructions between two source offsets, shortened to fit in a table.
+    fn snippet(source: &[u8], dialect: &Dialect, start: usize, end: usize) -> String {
+        let instructions: Vec<u8> = source[start..=end]
+            .iter()
+            .copied()
+            .filter(|&byte| Instruction::from_byte(byte, dialect).is_some())
+            .collect();
+
+        if instructions.len() > SNIPPET_LENGTH {
+            format!(
+                "{}...",
+                String::from_utf8_lossy(&instructions[..SNIPPET_LENGTH - 3usize])
+            )
+        } else {
+            String::from_utf8_lossy(&instructions).into_owned()
+        }
+    }
+
+    /// Source offset of the last instruction the op at `index` was built from: the closing
+    /// bracket of a folded loop, or the end of a run of `+` and `-` or `<` and `>`.
+    fn op_end(&self, source: &[u8], dialect: &Dialect, index: usize) -> usize {
+        let start: usize = self.ir.offsets[index];
+        let code: &[u8] = frontend::split_input(source, dialect).0;
+        let instructions = code
+            .iter()
+            .enumerate()
+            .skip(start)
+            .filter_map(|(offset, &byte)| Some((offset, Instruction::from_byte(byte, dialect)?)));
+
+        match self.ir.ops[index] {
+            Op::Add(_) => instructions
+                .take_while(|&(_, instruction)| {
+                    matches!(instruction, Instruction::Increment | Instruction::Decrement)
+                })
+                .last()
+                .map_or(start, |(offset, _)| offset),
+            Op::Move(distance) => instructions
+                .take_while(|&(_, instruction)| {
+                    instruction
+                        == if distance < 0isize {
+                            Instruction::MoveLeft
+                        } else {
+                            Instruction::MoveRight
+                        }
+                })
+                .last()
+                .map_or(start, |(offset, _)| offset),
+            Op::SetZero | Op::MulAdd { .. } | Op::ScanLeft(_) | Op::ScanRight(_) => {
+                let mut depth: usize = 0usize;
+                for (offset, instruction) in instructions {
+                    match instruction {
+                        Instruction::LoopStart => depth += 1usize,
+                        Instruction::LoopEnd if depth == 1usize => return offset,
+                        Instruction::LoopEnd => depth -= 1usize,
+                        _ => (),
+                    }
+                }
+                start
+            }
+            _ => start,
+        }
+    }
+
+    /// A report of the loops and ops that took the most steps. Steps are counted per op of the
+    /// optimized program rather than per instruction of the source, so that a run of `+` or a
+    /// folded `[-]` takes one step, and a scan such as `[<]` one step and one per cell it moves.
+    pub fn report(&self, source: &[u8], dialect: &Dialect) -> String {
+        let total_steps: u64 = self.total_steps();
+        let share = |steps: u64| -> f64 { 100f64 * steps as f64 / total_steps.max(1u64) as f64 };
+        let mut report: String = String::new();
+
+        writeln!(
+            report,
+            "profile: {} steps over {} optimized ops, one for each op run and each cell scanned",
+            total_steps,
+            self.ir.ops.len()
+        )
+        .unwrap();
+
+        let mut loops: Vec<LoopProfile> = self.loops();
+        loops.retain(|profile| profile.entries > 0u64);
+        loops.sort_by(|a, b| b.steps.cmp(&a.steps).then(a.start.cmp(&b.start)));
+
+        writeln!(report, "\nhot loops:").unwrap();
+        if loops.is_empty() {
+            writeln!(report, "  none executed").unwrap();
+        } else {
+            writeln!(
+                report,
+                "  {:<10} {:>14} {:>12} {:>16} {:>7}  loop",
+                "location", "iterations", "entries", "steps", "share"
+            )
+            .unwrap();
+        }
+        for profile in loops.iter().take(REPORT_ROWS) {
+            let location: Location = Location::from_offset(source, self.ir.offsets[profile.start]);
+            writeln!(
+                report,
+                "  {:<10} {:>14} {:>12} {:>16} {:>6.2}%  {}",
+                location.to_string(),
+                profile.iterations,
+                profile.entries,
+                profile.steps,
+                share(profile.steps),
+                Profile::snippet(
+                    source,
+                    dialect,
+                    self.ir.offsets[profile.start],
+                    self.ir.offsets[profile.end]
+                ),
+            )
+            .unwrap();
+        }
+
+        // Ops folded from one loop share its source offset and are reported together, as the
+        // first of them runs whenever the loop is reached.
+        let mut hottest: Vec<(usize, u64)> = (0..self.ir.ops.len())
+            .filter(|&i| i == 0usize || self.ir.offsets[i - 1usize] != self.ir.offsets[i])
+            .map(|i| {
+                let steps: u64 = (i..self.ir.ops.len())
+                    .take_while(|&j| self.ir.offsets[j] == self.ir.offsets[i])
+                    .map(|j| self.counts[j])
+                    .sum();
+                (i, steps)
+            })
+            .filter(|&(i, _)| self.counts[i] > 0u64)
+            .collect();
+        hottest.sort_by(|&(a, a_steps), &(b, b_steps)| b_steps.cmp(&a_steps).then(a.cmp(&b)));
+
+        writeln!(report, "\nhot instructions:").unwrap();
+        if hottest.is_empty() {
+            writeln!(report, "  none executed").unwrap();
+        } else {
+            writeln!(
+                report,
+                "  {:<10} {:>14} {:>7}  instructions",
+                "location", "steps", "share"
+            )
+            .unwrap();
+        }
+        for &(i, steps) in hottest.iter().take(REPORT_ROWS) {
+            writeln!(
+                report,
+                "  {:<10} {:>14} {:>6.2}%  {}",
+                Location::from_offset(source, self.ir.offsets[i]).to_string(),
+                steps,
+                share(steps),
+                Profile::snippet(
+                    source,
+                    dialect,
+                    self.ir.offsets[i],
+                    self.op_end(source, dialect, i)
+                ),
+            )
+            .unwrap();
+        }
+
+        report
+    }
+}
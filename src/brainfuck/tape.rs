@@ -0,0 +1,262 @@
+use std::fmt::{self, Debug, Display, Formatter, Write as _};
+
+/// A tape cell of a fixed bit width. All arithmetic wraps around at the width of the cell.
+pub trait Cell: Copy + Default + Eq + Debug {
+    const ZERO: Self;
+
+    /// Truncates an amount from the IR to the width of the cell.
+    fn from_i32(value: i32) -> Self;
+    fn from_byte(byte: u8) -> Self;
+    /// The low byte of the cell, which is what `.` writes.
+    fn to_byte(self) -> u8;
+    fn to_usize(self) -> usize;
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_mul(self, other: Self) -> Self;
+}
+
+macro_rules! impl_cell {
+    ($($cell:ty),*) => {
+        $(
+            impl Cell for $cell {
+                const ZERO: Self = 0;
+
+                fn from_i32(value: i32) -> Self {
+                    value as $cell
+                }
+
+                fn from_byte(byte: u8) -> Self {
+                    byte as $cell
+                }
+
+                fn to_byte(self) -> u8 {
+                    self as u8
+                }
+
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+
+                fn wrapping_add(self, other: Self) -> Self {
+                    <$cell>::wrapping_add(self, other)
+                }
+
+                fn wrapping_mul(self, other: Self) -> Self {
+                    <$cell>::wrapping_mul(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_cell!(u8, u16, u32);
+
+/// What happens when the memory pointer leaves the tape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bounds {
+    /// Continue from the opposite end of the tape.
+    Wrap,
+    /// Stop execution with a `TapeError`.
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TapeConfig {
+    pub length: usize,
+    /// Extend the tape instead of applying `bounds` when the pointer moves past its end.
+    pub growable: bool,
+    /// Most cells a growable tape may grow to, after which `bounds` applies again.
+    pub max_length: usize,
+    pub bounds: Bounds,
+}
+
+impl TapeConfig {
+    /// Cells the tape starts with, which it never shrinks below.
+    pub fn initial_length(&self) -> usize {
+        self.length.min(self.max_length).max(1usize)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapeError {
+    Underflow {
+        pointer: usize,
+        distance: usize,
+    },
+    Overflow {
+        pointer: usize,
+        distance: usize,
+        length: usize,
+    },
+    /// A tape of `length` cells does not fit in memory.
+    Allocation {
+        length: usize,
+    },
+}
+
+impl Display for TapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TapeError::Underflow { pointer, distance } => write!(
+                f,
+                "memory pointer moved {} cell(s) left from cell {}, past the start of the tape",
+                distance, pointer
+            ),
+            TapeError::Overflow {
+                pointer,
+                distance,
+                length,
+            } => write!(
+                f,
+                "memory pointer moved {} cell(s) right from cell {}, past the end of the {} cell tape",
+                distance, pointer, length
+            ),
+            TapeError::Allocation { length } => {
+                write!(f, "cannot allocate a tape of {} cells", length)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TapeError {}
+
+/// `length` zeroed cells, or an error instead of a panic if they do not fit in memory.
+pub fn zeroed<C: Cell>(length: usize) -> Result<Vec<C>, TapeError> {
+    let mut cells: Vec<C> = Vec::new();
+    cells
+        .try_reserve_exact(length)
+        .map_err(|_| TapeError::Allocation { length })?;
+    cells.resize(length, C::ZERO);
+    Ok(cells)
+}
+
+pub struct Tape<C: Cell> {
+    cells: Vec<C>,
+    pointer: usize,
+    growable: bool,
+    max_length: usize,
+    bounds: Bounds,
+}
+
+impl<C: Cell> Tape<C> {
+    pub fn new(config: &TapeConfig) -> Result<Tape<C>, TapeError> {
+        Ok(Tape {
+            cells: zeroed(config.initial_length())?,
+            pointer: 0usize,
+            growable: config.growable,
+            max_length: config.max_length,
+            bounds: config.bounds,
+        })
+    }
+
+    pub fn pointer(&self) -> usize {
+        self.pointer
+    }
+
+    pub fn cells(&self) -> &[C] {
+        &self.cells
+    }
+
+    /// The cells within `radius` of the pointer as rows of indices and values, with a caret
+    /// under the current cell.
+    pub fn window(&self, radius: usize) -> String {
+        let start: usize = self.pointer.saturating_sub(radius);
+        let end: usize = self
+            .pointer
+            .saturating_add(radius)
+            .min(self.cells.len() - 1usize)
+            + 1usize;
+
+        let mut indices: String = String::from("cell ");
+        let mut values: String = String::from("value");
+        let mut marker: String = String::from("     ");
+        for index in start..end {
+            let value: String = format!("{:?}", self.cells[index]);
+            let width: usize = index.to_string().len().max(value.len());
+            write!(indices, " {:>width$}", index).unwrap();
+            write!(values, " {:>width$}", value).unwrap();
+            write!(
+                marker,
+                " {:>width$}",
+                if index == self.pointer { "^" } else { "" }
+            )
+            .unwrap();
+        }
+
+        format!("{}\n{}\n{}", indices, values, marker.trim_end())
+    }
+
+    #[inline(always)]
+    pub fn current(&self) -> C {
+        self.cells[self.pointer]
+    }
+
+    #[inline(always)]
+    pub fn set_current(&mut self, value: C) {
+        self.cells[self.pointer] = value;
+    }
+
+    /// Index of the cell `distance` cells away from the pointer, growing the tape if allowed.
+    #[inline(always)]
+    fn resolve(&mut self, distance: isize) -> Result<usize, TapeError> {
+        match self.pointer.checked_add_signed(distance) {
+            Some(index) if index < self.cells.len() => Ok(index),
+            _ => self.resolve_outside(distance),
+        }
+    }
+
+    #[cold]
+    fn resolve_outside(&mut self, distance: isize) -> Result<usize, TapeError> {
+        let length: usize = self.cells.len();
+
+        if distance < 0 {
+            let distance: usize = distance.unsigned_abs();
+            if self.bounds == Bounds::Wrap {
+                Ok((self.pointer + length - distance % length) % length)
+            } else {
+                Err(TapeError::Underflow {
+                    pointer: self.pointer,
+                    distance,
+                })
+            }
+        } else {
+            let distance: usize = distance as usize;
+            let index: usize = self.pointer + distance;
+            if self.growable && index < self.max_length {
+                let new_length: usize = (index + 1usize)
+                    .max(length.saturating_mul(2usize))
+                    .min(self.max_length);
+                self.cells
+                    .try_reserve_exact(new_length - length)
+                    .map_err(|_| TapeError::Allocation { length: new_length })?;
+                self.cells.resize(new_length, C::ZERO);
+                Ok(index)
+            } else if self.bounds == Bounds::Wrap {
+                Ok(index % length)
+            } else {
+                Err(TapeError::Overflow {
+                    pointer: self.pointer,
+                    distance,
+                    // A growable tape is only full once it has grown as far as it may.
+                    length: if self.growable {
+                        self.max_length
+                    } else {
+                        length
+                    },
+                })
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn shift(&mut self, distance: isize) -> Result<(), TapeError> {
+        self.pointer = self.resolve(distance)?;
+        Ok(())
+    }
+
+    /// Adds `value` to the cell `offset` cells away from the pointer, without moving it.
+    pub fn add_at(&mut self, offset: isize, value: C) -> Result<(), TapeError> {
+        let index: usize = self.resolve(offset)?;
+        self.cells[index] = self.cells[index].wrapping_add(value);
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::{BufReader, Read, Write};
+
+use crate::frontend::Program;
+use crate::interpreters::{optimized, RuntimeError};
+use crate::io::{Console, Eof, IoConfig};
+use crate::ir::{self, Ir};
+use crate::limits::Limits;
+use crate::tape::{Cell, Tape, TapeConfig, TapeError};
+
+/// A brainfuck program bound to its own tape and to any reader and writer, so that it can be run
+/// from other code and its memory inspected afterwards.
+pub struct Vm<C: Cell, R: Read, W: Write> {
+    ir: Ir,
+    tape_config: TapeConfig,
+    tape: Tape<C>,
+    console: Console<BufReader<R>, W>,
+    eof: Eof,
+    limits: Limits,
+}
+
+impl<C: Cell, R: Read, W: Write> Vm<C, R, W> {
+    /// Fails if the tape does not fit in memory.
+    pub fn new(
+        program: &Program,
+        tape_config: &TapeConfig,
+        io_config: &IoConfig,
+        reader: R,
+        writer: W,
+    ) -> Result<Vm<C, R, W>, TapeError> {
+        let mut console: Console<BufReader<R>, W> =
+            Console::with_io(BufReader::new(reader), writer, io_config);
+        console.embed_input(&program.input);
+
+        Ok(Vm {
+            ir: ir::build_for_tape(program, tape_config),
+            tape_config: *tape_config,
+            tape: Tape::new(tape_config)?,
+            console,
+            eof: io_config.eof,
+            limits: Limits::default(),
+        })
+    }
+
+    /// Bounds every later `run`, which is unlimited by default.
+    pub fn set_limits(&mut self, limits: &Limits) {
+        self.limits = *limits;
+    }
+
+    /// Runs the program with the optimized interpreter and flushes the writer. Every run starts
+    /// on a cleared tape, which the optimizer counts on, and leaves the tape as the program left
+    /// it, including when it stops with an error.
+    pub fn run(&mut self) -> Result<(), RuntimeError> {
+        self.tape = Tape::new(&self.tape_config)?;
+        optimized::execute::<C, _, _, false>(
+            &self.ir,
+            &mut self.tape,
+            &mut self.console,
+            self.eof,
+            &self.limits,
+            &mut [],
+        )?;
+        self.console.flush()?;
+        Ok(())
+    }
+
+    pub fn tape(&self) -> &[C] {
+        self.tape.cells()
+    }
+
+    pub fn pointer(&self) -> usize {
+        self.tape.pointer()
+    }
+
+    /// The writer, with everything the program wrote to it.
+    pub fn into_writer(self) -> W {
+        self.console.into_writer()
+    }
+}
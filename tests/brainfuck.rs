@@ -0,0 +1,1274 @@
+//! Tests of the brainfuck tool through its command line, and of its library.
+//!
+//! Every program in `resources/brainfuck_programs` is run in each interpreter mode and through
+//! each compiler target that can run here, fed `expected/NAME.in` if there is one, and its output
+//! compared against `expected/NAME.out`. The other tests each cover a subcommand or an option.
+
+use std::env::temp_dir;
+use std::fs::{create_dir_all, read, remove_dir_all, write, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Output, Stdio};
+
+use brainfuck::frontend::{self, Dialect, Program};
+use brainfuck::io::{Eof, IoConfig, IoMode};
+use brainfuck::tape::{Bounds, TapeConfig};
+use brainfuck::vm::Vm;
+
+const BRAINFUCK: &str = env!("CARGO_BIN_EXE_brainfuck");
+const PROGRAMS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/resources/brainfuck_programs");
+
+#[cfg(all(target_arch = "x86_64", unix))]
+const MODES: [&str; 3] = ["basic", "optimized", "jit"];
+#[cfg(not(all(target_arch = "x86_64", unix)))]
+const MODES: [&str; 2] = ["basic", "optimized"];
+
+fn stdin(golden: &str) -> Stdio {
+    let path_buf: PathBuf = Path::new(PROGRAMS)
+        .join("expected")
+        .join(format!("{}.in", golden));
+    if path_buf.exists() {
+        Stdio::from(File::open(path_buf).unwrap())
+    } else {
+        Stdio::null()
+    }
+}
+
+/// Runs `command` with the golden input and returns its stdout, failing the test on a non-zero
+/// exit status.
+fn run(command: &mut Command, golden: &str) -> Vec<u8> {
+    let output: Output = command.stdin(stdin(golden)).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{:?} failed with {}:\n{}",
+        command,
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+/// Runs `command` with no input and returns its stderr, failing the test unless it exits with
+/// status 1, which is how the tool reports errors in the program or its options.
+fn run_failing(command: &mut Command) -> String {
+    let output: Output = command.stdin(Stdio::null()).output().unwrap();
+    let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(
+        output.status.code() == Some(1i32),
+        "{:?} exited with {} instead of failing:\n{}",
+        command,
+        output.status,
+        stderr
+    );
+    stderr
+}
+
+/// Runs `command` with `script` as the commands of an interactive session on stdin, and returns
+/// its stdout and stderr, failing the test on a non-zero exit status.
+fn run_scripted(command: &mut Command, script: &[u8]) -> (Vec<u8>, String) {
+    let mut child: process::Child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(script).unwrap();
+    let output: Output = child.wait_with_output().unwrap();
+    let messages: String = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        output.status.success(),
+        "{:?} failed with {}:\n{}",
+        command,
+        output.status,
+        messages
+    );
+    (output.stdout, messages)
+}
+
+/// A directory of its own under the system temporary directory, removed with everything in it
+/// when dropped, even by a failing test.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let path: PathBuf = temp_dir().join(format!("brainfuck-{}-{}", name, process::id()));
+        create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    fn join<P: AsRef<Path>>(&self, name: P) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.0);
+    }
+}
+
+fn assert_output(actual: Vec<u8>, expected: &[u8], golden: &str, runner: &str) {
+    assert!(
+        actual == expected,
+        "{} output of `{}` differs from expected/{}.out\nexpected:\n{}\nactual:\n{}",
+        runner,
+        golden,
+        golden,
+        String::from_utf8_lossy(expected),
+        String::from_utf8_lossy(&actual)
+    );
+}
+
+/// Checks the shape of a WebAssembly text module without a runtime: one module of balanced
+/// parentheses that exports its entry point for WASI.
+fn check_module(module: &str, golden: &str) {
+    let mut depth: usize = 0usize;
+    for character in module.chars() {
+        match character {
+            '(' => depth += 1usize,
+            ')' => {
+                depth = depth
+                    .checked_sub(1usize)
+                    .unwrap_or_else(|| panic!("unmatched `)` in the {} module", golden))
+            }
+            _ => (),
+        }
+    }
+    assert_eq!(depth, 0usize, "unclosed `(` in the {} module", golden);
+    assert!(
+        module.starts_with("(module\n"),
+        "{} module:\n{}",
+        golden,
+        module
+    );
+    assert!(
+        module.contains("(func $main (export \"_start\")"),
+        "{} module:\n{}",
+        golden,
+        module
+    );
+}
+
+/// Checks every interpreter mode and compiler target against `expected/GOLDEN.out`, running
+/// `PROGRAM.b` with `options` passed to both the interpreter and the compiler.
+fn check(program: &str, golden: &str, options: &[&str]) {
+    let source_path: PathBuf = Path::new(PROGRAMS).join(format!("{}.b", program));
+    let expected: Vec<u8> = read(
+        Path::new(PROGRAMS)
+            .join("expected")
+            .join(format!("{}.out", golden)),
+    )
+    .unwrap();
+
+    for mode in MODES {
+        let output: Vec<u8> = run(
+            Command::new(BRAINFUCK)
+                .arg(&source_path)
+                .args(["interpreter", "--mode", mode])
+                .args(options),
+            golden,
+        );
+        assert_output(output, &expected, golden, &format!("{} interpreter", mode));
+    }
+
+    let directory: TempDir = TempDir::new(golden);
+
+    let executable: PathBuf = directory.join(golden);
+    run(
+        Command::new(BRAINFUCK)
+            .arg(&source_path)
+            .args(["compiler", "--output"])
+            .arg(&executable)
+            .args(options),
+        golden,
+    );
+    assert_output(
+        run(&mut Command::new(&executable), golden),
+        &expected,
+        golden,
+        "rust target",
+    );
+
+    #[cfg(unix)]
+    for (target, extension) in [("c", "c"), ("asm", "s")] {
+        if target == "asm" && !cfg!(all(target_arch = "x86_64", target_os = "linux")) {
+            continue;
+        }
+
+        let emitted_path: PathBuf = directory.join(format!("{}.{}", golden, extension));
+        let executable: PathBuf = directory.join(format!("{}_{}", golden, target));
+        run(
+            Command::new(BRAINFUCK)
+                .arg(&source_path)
+                .args(["compiler", "--target", target, "--output"])
+                .arg(&emitted_path)
+                .args(options),
+            golden,
+        );
+        run(
+            Command::new("cc")
+                .args(["-O2", "-o"])
+                .arg(&executable)
+                .arg(&emitted_path),
+            golden,
+        );
+        assert_output(
+            run(&mut Command::new(&executable), golden),
+            &expected,
+            golden,
+            &format!("{} target", target),
+        );
+    }
+
+    let module_path: PathBuf = directory.join(format!("{}.wat", golden));
+    run(
+        Command::new(BRAINFUCK)
+            .arg(&source_path)
+            .args(["compiler", "--target", "wat", "--output"])
+            .arg(&module_path)
+            .args(options),
+        golden,
+    );
+    check_module(
+        &String::from_utf8(read(&module_path).unwrap()).unwrap(),
+        golden,
+    );
+    // Running the module needs a WASI runtime, so it is only run where one is installed.
+    if Command::new("wasmtime").arg("--version").output().is_err() {
+        eprintln!("{}: wasmtime not found, not running the wat target", golden);
+    } else {
+        assert_output(
+            run(Command::new("wasmtime").arg(&module_path), golden),
+            &expected,
+            golden,
+            "wat target",
+        );
+    }
+}
+
+/// Checks that `fmt` and `minify` keep the output of `PROGRAM.b` unchanged, and that formatting
+/// is idempotent.
+fn check_rewrites(program: &str) {
+    let source_path: PathBuf = Path::new(PROGRAMS).join(format!("{}.b", program));
+    let expected: Vec<u8> = read(
+        Path::new(PROGRAMS)
+            .join("expected")
+            .join(format!("{}.out", program)),
+    )
+    .unwrap();
+
+    let directory: TempDir = TempDir::new(&format!("rewrite-{}", program));
+
+    for subcommand in ["fmt", "minify"] {
+        let rewritten_path: PathBuf = directory.join(format!("{}.b", subcommand));
+        run(
+            Command::new(BRAINFUCK)
+                .arg(&source_path)
+                .args([subcommand, "--output"])
+                .arg(&rewritten_path),
+            program,
+        );
+        let output: Vec<u8> = run(
+            Command::new(BRAINFUCK)
+                .arg(&rewritten_path)
+                .arg("interpreter"),
+            program,
+        );
+        assert_output(
+            output,
+            &expected,
+            program,
+            &format!("{} program", subcommand),
+        );
+    }
+
+    let formatted_path: PathBuf = directory.join("fmt.b");
+    let reformatted: Vec<u8> = run(
+        Command::new(BRAINFUCK).arg(&formatted_path).arg("fmt"),
+        program,
+    );
+    assert!(
+        reformatted == read(&formatted_path).unwrap(),
+        "formatting `{}` twice changes it",
+        program
+    );
+}
+
+#[test]
+fn parse_errors() {
+    let directory: TempDir = TempDir::new("parse-errors");
+    let program_path: PathBuf = directory.join("unclosed.b");
+    write(&program_path, "+++\n  +[>+.\n").unwrap();
+    let stderr: String = run_failing(
+        Command::new(BRAINFUCK)
+            .arg(&program_path)
+            .arg("interpreter"),
+    );
+    assert_eq!(
+        stderr,
+        format!(
+            "error: unmatched `[`, loop is never closed\n --> {}:2:4\n  |\n2 |   +[>+.\n  |    ^\n",
+            program_path.display()
+        )
+    );
+
+    let stderr: String = run_failing(Command::new(BRAINFUCK).args(["--eval", "+]", "interpreter"]));
+    assert_eq!(
+        stderr,
+        "error: unmatched `]`, no loop to close\n --> <eval>:1:2\n  |\n1 | +]\n  |  ^\n"
+    );
+}
+
+#[test]
+fn oversized_tape() {
+    for mode in MODES {
+        let stderr: String = run_failing(Command::new(BRAINFUCK).args([
+            "--eval",
+            "+.",
+            "interpreter",
+            "--mode",
+            mode,
+            "--tape-size",
+            "18446744073709551615",
+        ]));
+        assert!(
+            stderr.starts_with("error: cannot allocate a tape of 18446744073709551615 cells"),
+            "{} interpreter: {}",
+            mode,
+            stderr
+        );
+
+        // `--max-tape-size` caps the tape before it is allocated.
+        let output: Vec<u8> = run(
+            Command::new(BRAINFUCK).args([
+                "--eval",
+                "+++++++++[>++++++++<-]>.",
+                "interpreter",
+                "--mode",
+                mode,
+                "--tape-size",
+                "18446744073709551615",
+                "--max-tape-size",
+                "16",
+            ]),
+            "oversized_tape",
+        );
+        assert_eq!(output, b"H", "{} interpreter", mode);
+    }
+}
+
+#[test]
+fn tape_options() {
+    // Needs a tape of 9 cells.
+    let far: &str = ">>>>>>>>+++++++++[<++++++++>-]<.";
+    // Counts down in the last cell of a 2 cell tape, and adds to the first.
+    let wrapping: &str = "<+++++++++[>++++++++<-]>.";
+    // Only prints if a cell holds 256.
+    let wide: &str =
+        "++++++++[>++++++++++++++++++++++++++++++++<-]>[[-]<+++++++++[>++++++++<-]>.[-]]";
+
+    // The JIT takes none of these but 8-bit cells.
+    for mode in ["basic", "optimized"] {
+        for (program, options, expected) in [
+            (far, &["--tape-size", "1", "--grow"][..], &b"H"[..]),
+            (wrapping, &["--tape-size", "2", "--bounds", "wrap"], b"H"),
+            (wide, &["--cell-bits", "8"], b""),
+            (wide, &["--cell-bits", "16"], b"H"),
+            (wide, &["--cell-bits", "32"], b"H"),
+        ] {
+            let output: Vec<u8> = run(
+                Command::new(BRAINFUCK)
+                    .args(["--eval", program, "interpreter", "--mode", mode])
+                    .args(options),
+                "tape_options",
+            );
+            assert_eq!(output, expected, "{} interpreter with {:?}", mode, options);
+        }
+
+        for (program, options, error) in [
+            (
+                far,
+                &["--tape-size", "1", "--grow", "--max-tape-size", "4"][..],
+                "past the end of the 4 cell tape",
+            ),
+            (
+                wrapping,
+                &["--tape-size", "2"],
+                "past the start of the tape",
+            ),
+        ] {
+            let stderr: String = run_failing(
+                Command::new(BRAINFUCK)
+                    .args(["--eval", program, "interpreter", "--mode", mode])
+                    .args(options),
+            );
+            assert!(
+                stderr.contains(error),
+                "{} interpreter with {:?}: {}",
+                mode,
+                options,
+                stderr
+            );
+        }
+    }
+}
+
+/// Loops the optimized interpreter folds must still stop, fail or go on where the basic
+/// interpreter does, wherever the loop moves and however the tape is bounded.
+#[test]
+fn folded_loops() {
+    for (program, options) in [
+        // The only other cell is the counter itself.
+        (
+            "+++[>+<-]",
+            &[
+                "--tape-size",
+                "1",
+                "--bounds",
+                "wrap",
+                "--max-steps",
+                "1000",
+            ][..],
+        ),
+        ("+++[>>+<<-]>>.", &["--tape-size", "3", "--bounds", "wrap"]),
+        // The body moves past the last cell it adds to.
+        ("+[->>><+<<]", &["--tape-size", "3"]),
+        ("+[->>><+<<]", &["--tape-size", "1", "--grow"]),
+        ("+[-<+>]", &["--tape-size", "3"]),
+        ("><+.", &["--tape-size", "1"]),
+    ] {
+        let outputs: Vec<(Option<i32>, Vec<u8>)> = ["basic", "optimized"]
+            .iter()
+            .map(|mode| {
+                let output: Output = Command::new(BRAINFUCK)
+                    .args(["--eval", program, "interpreter", "--mode", mode])
+                    .args(options)
+                    .stdin(Stdio::null())
+                    .output()
+                    .unwrap();
+                (output.status.code(), output.stdout)
+            })
+            .collect();
+        assert_eq!(
+            outputs[0], outputs[1],
+            "basic and optimized differ on `{}` with {:?}",
+            program, options
+        );
+    }
+}
+
+#[test]
+fn dialect_extensions() {
+    // The program, the extension it needs, its output, and what it prints to stderr.
+    let programs: [(&str, &str, &[u8], &str); 3] = [
+        ("++++++++[>++++++++<-]>+.#", "dump", b"A", "value 0 65 0"),
+        (",.,.!hi", "embedded-input", b"hi", ""),
+        ("+(>++++++++[>++++++++<-]>+.[-]<<)::", "pbrain", b"AA", ""),
+    ];
+
+    for (program, extension, expected, messages) in programs {
+        for mode in MODES {
+            let mut command: Command = Command::new(BRAINFUCK);
+            command.args([
+                "--eval",
+                program,
+                "interpreter",
+                "--mode",
+                mode,
+                "--dialect",
+                extension,
+            ]);
+            // The JIT reads embedded input like any other input, but runs no other extension.
+            if mode == "jit" && extension != "embedded-input" {
+                let stderr: String = run_failing(&mut command);
+                assert!(
+                    stderr.starts_with("error: the JIT does not support dialect extensions"),
+                    "{}: {}",
+                    extension,
+                    stderr
+                );
+                continue;
+            }
+
+            let output: Output = command.stdin(Stdio::null()).output().unwrap();
+            let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+            assert!(
+                output.status.success(),
+                "{} {}: {}",
+                mode,
+                extension,
+                stderr
+            );
+            assert_eq!(output.stdout, expected, "{} {}", mode, extension);
+            assert!(
+                stderr.contains(messages),
+                "{} {}: {}",
+                mode,
+                extension,
+                stderr
+            );
+        }
+    }
+}
+
+/// `[>]` on a wrapping tape of one nonzero cell never finds a zero, which the optimized
+/// interpreter runs as a single scan.
+const ENDLESS_SCAN: &str = "+[>]";
+
+#[test]
+fn limits() {
+    // The JIT refuses limits, and wrapping tapes.
+    for mode in ["basic", "optimized"] {
+        for (limit, value, message) in [
+            ("--max-steps", "100", "error: step limit of 100 reached"),
+            ("--timeout", "0.5", "error: time limit of 500ms reached"),
+        ] {
+            let stderr: String = run_failing(Command::new(BRAINFUCK).args([
+                "--eval",
+                ENDLESS_SCAN,
+                "interpreter",
+                "--mode",
+                mode,
+                "--bounds",
+                "wrap",
+                "--tape-size",
+                "1",
+                limit,
+                value,
+            ]));
+            assert!(
+                stderr.starts_with(message) && stderr.contains("memory pointer at cell 0"),
+                "{} interpreter with {} {}: {}",
+                mode,
+                limit,
+                value,
+                stderr
+            );
+        }
+    }
+    // The scan is stopped at its loop.
+    let stderr: String = run_failing(Command::new(BRAINFUCK).args([
+        "--eval",
+        ENDLESS_SCAN,
+        "interpreter",
+        "--bounds",
+        "wrap",
+        "--tape-size",
+        "1",
+        "--max-steps",
+        "100",
+    ]));
+    assert!(stderr.contains("--> <eval>:1:2"), "{}", stderr);
+
+    let mut child: process::Child = Command::new(BRAINFUCK)
+        .args(["repl", "--bounds", "wrap", "--tape-size", "1"])
+        .args(["--max-steps", "100"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(format!("{}\n", ENDLESS_SCAN).as_bytes())
+        .unwrap();
+    let output: Output = child.wait_with_output().unwrap();
+    let messages: String = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        messages.contains("error: step limit of 100 reached"),
+        "{}",
+        messages
+    );
+
+    let directory: TempDir = TempDir::new("limits");
+    write(directory.join("endless.b"), ENDLESS_SCAN).unwrap();
+    let output: Output = Command::new(BRAINFUCK)
+        .arg("run-all")
+        .arg(directory.path())
+        .args(["--bounds", "wrap", "--tape-size", "1", "--timeout", "0.5"])
+        .output()
+        .unwrap();
+    let summary: String = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(output.status.code(), Some(1i32), "{}", summary);
+    assert!(
+        summary.contains("time limit of 500ms reached"),
+        "{}",
+        summary
+    );
+
+    for timeout in ["inf", "NaN", "1e30", "-1"] {
+        let output: Output = Command::new(BRAINFUCK)
+            .args(["--eval", "+", "interpreter"])
+            .arg(format!("--timeout={}", timeout))
+            .output()
+            .unwrap();
+        let stderr: String = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            output.status.code() == Some(2i32) && stderr.contains("--timeout"),
+            "--timeout {} was accepted: {}",
+            timeout,
+            stderr
+        );
+    }
+}
+
+/// `,[-<+>]` never touches the cell left of cell 0 when it reads a zero, so the C, assembly and
+/// WebAssembly it compiles to must not either.
+#[test]
+fn profile() {
+    // Three cells for the scan to move past, then a folded multiplication.
+    const PROGRAM: &str = ">+>+>+[<]+++++++[>+++++++<-]>.";
+    let output: Output = Command::new(BRAINFUCK)
+        .args(["--eval", PROGRAM, "interpreter", "--profile"])
+        .output()
+        .unwrap();
+    let report: String = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "profile failed:\n{}", report);
+    assert_output(output.stdout, b"2", "profile", "profiled interpreter");
+
+    assert!(
+        report.starts_with("profile: 15 steps over 12 optimized ops"),
+        "{}",
+        report
+    );
+    for row in [
+        "1:7                     4  26.67%  [<]",
+        "1:17                    2  13.33%  [>+++++++<-]",
+        "1:10                    1   6.67%  +++++++",
+    ] {
+        assert!(report.contains(row), "no {:?} in:\n{}", row, report);
+    }
+
+    // The profile counts steps as `--max-steps` does.
+    run(
+        Command::new(BRAINFUCK).args(["--eval", PROGRAM, "interpreter", "--max-steps", "15"]),
+        "profile",
+    );
+    let stderr: String = run_failing(Command::new(BRAINFUCK).args([
+        "--eval",
+        PROGRAM,
+        "interpreter",
+        "--max-steps",
+        "14",
+    ]));
+    assert!(
+        stderr.starts_with("error: step limit of 14 reached"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn guarded_multiplication() {
+    let directory: TempDir = TempDir::new("guard");
+    // The first loop never runs, and folded without its guard it would add zero to a cell far
+    // left of the tape, which is out of the process's memory.
+    let program_path: PathBuf = directory.join("guard.b");
+    write(
+        &program_path,
+        format!(
+            ">[-{}+{}]++++++++[>++++++++<-]>+.",
+            "<".repeat(100000usize),
+            ">".repeat(100000usize)
+        ),
+    )
+    .unwrap();
+
+    #[cfg(unix)]
+    for (target, extension) in [("c", "c"), ("asm", "s")] {
+        if target == "asm" && !cfg!(all(target_arch = "x86_64", target_os = "linux")) {
+            continue;
+        }
+
+        let emitted_path: PathBuf = directory.join(format!("guard.{}", extension));
+        let executable: PathBuf = directory.join(format!("guard_{}", target));
+        run(
+            Command::new(BRAINFUCK)
+                .arg(&program_path)
+                .args(["compiler", "--target", target, "--output"])
+                .arg(&emitted_path),
+            "guard",
+        );
+        run(
+            Command::new("cc")
+                .args(["-O2", "-o"])
+                .arg(&executable)
+                .arg(&emitted_path),
+            "guard",
+        );
+        assert_output(
+            run(&mut Command::new(&executable), "guard"),
+            b"A",
+            "guard",
+            &format!("{} target", target),
+        );
+    }
+
+    // Without a WASI runtime to run it, only the shape of the guard in the module is checked.
+    let module_path: PathBuf = directory.join("guard.wat");
+    run(
+        Command::new(BRAINFUCK)
+            .arg(&program_path)
+            .args(["compiler", "--target", "wat", "--output"])
+            .arg(&module_path),
+        "guard",
+    );
+    let module: String = String::from_utf8(read(&module_path).unwrap()).unwrap();
+    assert!(
+        module.contains("(if (i32.load8_u (local.get $p))\n"),
+        "wat target:\n{}",
+        module
+    );
+}
+
+#[test]
+fn emit_source() {
+    let directory: TempDir = TempDir::new("emit-source");
+    let source_path: PathBuf = directory.join("emitted.rs");
+    run(
+        Command::new(BRAINFUCK)
+            .args(["--eval", "++++++++[>++++++++<-]>+.", "compiler", "--output"])
+            .arg(directory.join("compiled"))
+            .arg("--emit-source")
+            .arg(&source_path),
+        "emit-source",
+    );
+
+    let source: String = String::from_utf8(read(&source_path).unwrap()).unwrap();
+    for operation in [
+        "wrapping_add(8u8)",
+        "// Folded loop at source offset 8",
+        "wrapping_mul(8u8)",
+        "memory_pointer += 1usize;",
+        "write_all(&[memory[memory_pointer]])",
+    ] {
+        assert!(
+            source.contains(operation),
+            "no {} in:\n{}",
+            operation,
+            source
+        );
+    }
+
+    // The kept source builds on its own into a program that behaves like the compiled one.
+    let executable: PathBuf = directory.join("rebuilt");
+    run(
+        Command::new("rustc")
+            .arg(&source_path)
+            .arg("-o")
+            .arg(&executable),
+        "emit-source",
+    );
+    assert_output(
+        run(&mut Command::new(&executable), "emit-source"),
+        b"A",
+        "emit-source",
+        "rebuilt source",
+    );
+    assert_output(
+        run(&mut Command::new(directory.join("compiled")), "emit-source"),
+        b"A",
+        "emit-source",
+        "rust target",
+    );
+}
+
+#[test]
+fn rewrites() {
+    for program in [
+        "brainfuck",
+        "hanoi",
+        "hello_world",
+        "life",
+        "printable",
+        "test",
+    ] {
+        check_rewrites(program);
+    }
+
+    // Cancelling pairs go, however they nest, and comments and whitespace with them.
+    for (program, minified) in [
+        ("+-<>+", "+"),
+        ("+[-+<>] ><.", "+[]."),
+        ("add +<<->>- one", "+<<->>-"),
+        ("><+-+--+", ""),
+    ] {
+        let output: Vec<u8> = run(
+            Command::new(BRAINFUCK).args(["--eval", program, "minify"]),
+            "minify",
+        );
+        assert_output(output, minified.as_bytes(), "minify", program);
+    }
+}
+
+#[test]
+fn bench() {
+    let report: Vec<u8> = run(
+        Command::new(BRAINFUCK)
+            .arg(Path::new(PROGRAMS).join("test.b"))
+            .arg("bench"),
+        "test",
+    );
+    let report: String = String::from_utf8(report).unwrap();
+
+    for backend in MODES.iter().chain(&["compiled"]) {
+        assert!(
+            report
+                .lines()
+                .any(|line| line.split_whitespace().next() == Some(backend)),
+            "no {} timing in report:\n{}",
+            backend,
+            report
+        );
+    }
+    assert!(
+        !report.contains("failed") && !report.contains("warning"),
+        "backends disagree:\n{}",
+        report
+    );
+
+    // Speedups are relative to the basic interpreter, the only backend counting instructions.
+    for line in report.lines().skip(1usize) {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        assert!(
+            (columns[0] == "basic") == (columns[2] != "-"),
+            "instructions counted by the wrong backend:\n{}",
+            report
+        );
+        if columns[0] == "basic" {
+            assert_eq!(
+                columns[3], "1.00x",
+                "basic is not the baseline:\n{}",
+                report
+            );
+        }
+    }
+}
+
+#[test]
+fn static_check() {
+    let report: Vec<u8> = run(
+        Command::new(BRAINFUCK)
+            .arg(Path::new(PROGRAMS).join("hello_world.b"))
+            .arg("check"),
+        "hello_world",
+    );
+    let report: String = String::from_utf8(report).unwrap();
+    assert!(
+        report.ends_with("so a tape of 31 cells is enough\n"),
+        "unexpected report:\n{}",
+        report
+    );
+    run(
+        Command::new(BRAINFUCK)
+            .arg(Path::new(PROGRAMS).join("hello_world.b"))
+            .args(["interpreter", "--tape-size", "31"]),
+        "hello_world",
+    );
+
+    // Clearing a cell that is already zero is a loop that never runs.
+    let report: String = String::from_utf8(run(
+        Command::new(BRAINFUCK).args(["--eval", "[-]+[-]>[-]", "check"]),
+        "check",
+    ))
+    .unwrap();
+    assert!(
+        report.starts_with(
+            "warning: loop never runs, the current cell is always zero here\n --> <eval>:1:1\n"
+        ) && report.contains("--> <eval>:1:9\n")
+            && !report.contains("--> <eval>:1:5\n")
+            && report.contains("3 loops, 3 with balanced pointer movement, 2 never run\n"),
+        "unexpected report:\n{}",
+        report
+    );
+
+    // An underflow fails the check, and no tape size is claimed to be enough.
+    for program in ["+[<+>-]", "<<<>>>+"] {
+        let output: Output = Command::new(BRAINFUCK)
+            .args(["--eval", program, "check"])
+            .output()
+            .unwrap();
+        let report: String = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            output.status.code() == Some(1i32),
+            "check accepted the tape underflow of {}",
+            program
+        );
+        assert!(
+            report.starts_with("error: the memory pointer moves left of cell 0\n")
+                && !report.contains("enough"),
+            "unexpected report for {}:\n{}",
+            program,
+            report
+        );
+    }
+}
+
+#[test]
+fn generated_programs() {
+    let directory: TempDir = TempDir::new("generate");
+    let program_path: PathBuf = directory.join("generated.b");
+
+    for text in [
+        "",
+        "a",
+        "Hello, World!\n",
+        "zyx ZYX 987 ~}| !\"#",
+        "\n\n\t aaaa",
+    ] {
+        run(
+            Command::new(BRAINFUCK)
+                .args(["generate", text, "--output"])
+                .arg(&program_path),
+            "generated",
+        );
+        assert!(
+            !read(&program_path).unwrap().starts_with(b"><"),
+            "the program generated for {:?} starts with `><`",
+            text
+        );
+        for mode in MODES {
+            let output: Vec<u8> = run(
+                Command::new(BRAINFUCK)
+                    .arg(&program_path)
+                    .args(["interpreter", "--mode", mode]),
+                "generated",
+            );
+            assert_output(
+                output,
+                text.as_bytes(),
+                "generated",
+                &format!("{} interpreter", mode),
+            );
+        }
+    }
+}
+
+#[test]
+fn eof() {
+    // Prints the cell `,` leaves at EOF, then `1` if one more makes it nonzero and `0` otherwise,
+    // which tells -1 from 255 in wider cells.
+    let program: String = format!(
+        "++++++++[>++++++++<-]>+,.+[[-]>+<]>{}.",
+        "+".repeat(48usize)
+    );
+
+    for (eof, expected) in [
+        ("unchanged", b"A1"),
+        ("zero", b"\x001"),
+        ("minus-one", b"\xff0"),
+    ] {
+        for cell_bits in ["8", "16", "32"] {
+            // The JIT only has 8-bit cells.
+            for mode in MODES
+                .iter()
+                .filter(|&&mode| mode != "jit" || cell_bits == "8")
+            {
+                let output: Vec<u8> = run(
+                    Command::new(BRAINFUCK).args([
+                        "--eval",
+                        &program,
+                        "interpreter",
+                        "--raw",
+                        "--eof",
+                        eof,
+                        "--cell-bits",
+                        cell_bits,
+                        "--mode",
+                        mode,
+                    ]),
+                    "eof",
+                );
+                assert_eq!(
+                    output, expected,
+                    "{} interpreter with {}-bit cells and --eof {}",
+                    mode, cell_bits, eof
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn source_and_input_streams() {
+    let cat_input: PathBuf = Path::new(PROGRAMS).join("expected").join("cat.in");
+    let cat_expected: Vec<u8> = read(Path::new(PROGRAMS).join("expected").join("cat.out")).unwrap();
+    let hello_expected: Vec<u8> =
+        read(Path::new(PROGRAMS).join("expected").join("hello_world.out")).unwrap();
+
+    for mode in MODES {
+        // Nothing is on stdin, so all input has to come from the file.
+        let output: Vec<u8> = run(
+            Command::new(BRAINFUCK)
+                .args([
+                    "--eval",
+                    ",[.,]",
+                    "interpreter",
+                    "--eof",
+                    "zero",
+                    "--mode",
+                    mode,
+                ])
+                .arg("--input")
+                .arg(&cat_input),
+            "eval",
+        );
+        assert_output(output, &cat_expected, "cat", &format!("{} --eval", mode));
+
+        let output: Output = Command::new(BRAINFUCK)
+            .args(["-", "interpreter", "--mode", mode])
+            .stdin(File::open(Path::new(PROGRAMS).join("hello_world.b")).unwrap())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_output(
+            output.stdout,
+            &hello_expected,
+            "hello_world",
+            &format!("{} from stdin", mode),
+        );
+    }
+
+    let output: Output = Command::new(BRAINFUCK)
+        .args(["--eval", "+", "compiler"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "compiled without an output path");
+}
+
+#[test]
+fn explain() {
+    let explanation: Vec<u8> = run(
+        Command::new(BRAINFUCK).args(["--eval", "++++[>+++<-]>[<+>[-]]<.+[>>>]<<,", "explain"]),
+        "explain",
+    );
+    let expected: &str = "\
+p[0] += 4;
+p[1] += p[0] * 3;
+p[0] = 0;
+if (p[1]) {
+    p[0] += 1;
+    p[1] = 0;
+}
+putchar(p[0]);
+p[0] += 1;
+while (p[0]) p += 3;
+p[-2] = getchar();
+";
+    assert_output(explanation, expected.as_bytes(), "explain", "explain");
+
+    for program in ["brainfuck", "life"] {
+        run(
+            Command::new(BRAINFUCK)
+                .arg(Path::new(PROGRAMS).join(format!("{}.b", program)))
+                .arg("explain"),
+            "explain",
+        );
+    }
+}
+
+#[test]
+fn repl() {
+    // The loop spans two lines, the tape outlives the error, and `reset` clears it. A radius
+    // reaching past the largest index shows the tape up to its end.
+    let (output, messages): (Vec<u8>, String) = run_scripted(
+        Command::new(BRAINFUCK).args(["repl", "--tape-size", "4"]),
+        b"++++++++[>++++++++\n<-]>+.\n]\n+.\ntape\nt 18446744073709551615\nreset\n+++++++++[>++++++++<-]>.\n",
+    );
+
+    assert_output(output, b"ABH", "repl", "repl");
+    assert!(messages.contains("unmatched `]`"), "{}", messages);
+    assert!(messages.contains("value 0 66 0 0\n"), "{}", messages);
+}
+
+#[test]
+fn debugger() {
+    let directory: TempDir = TempDir::new("debug");
+    let program_path: PathBuf = directory.join("echo.b");
+    write(&program_path, ",[.,]#+").unwrap();
+    let input_path: PathBuf = directory.join("echo.in");
+    write(&input_path, "abc").unwrap();
+
+    let (output, messages): (Vec<u8>, String) = run_scripted(
+        Command::new(BRAINFUCK)
+            .arg(&program_path)
+            .args(["debug", "--tape-size", "4", "--eof", "zero", "--input"])
+            .arg(&input_path),
+        b"break 1:3\nwatch 0\ncontinue\ncontinue\nstep 2\ntape\nt 18446744073709551615\ninfo\n\
+          unwatch 0\ndelete 1:3\ncontinue\ncontinue\n",
+    );
+
+    // The program reads its input from the file, not from the commands.
+    assert_output(output, b"abc", "debug", "debugger");
+    for message in [
+        "1 breakpoint(s) from `#`",
+        "breakpoint at 1:3\n",
+        "watching cell 0 = 0\n",
+        "cell 0 changed from 0 to 97\nat 1:2 `[` (instruction 1, step 1)",
+        "breakpoint\nat 1:3 `.` (instruction 2, step 2)",
+        "cell 0 changed from 97 to 98\nat 1:5 `]` (instruction 4, step 4)",
+        "value 98 0 0 0\n",
+        "memory pointer at cell 0\nbreakpoint at 1:3\nbreakpoint at 1:7\nwatching cell 0 = 98\n",
+        "deleted breakpoint at 1:3\n",
+        "breakpoint\nat 1:7 `+` (instruction 5, step 11)",
+        "program finished after 12 steps\n",
+    ] {
+        assert!(
+            messages.contains(message),
+            "no {:?} in:\n{}",
+            message,
+            messages
+        );
+    }
+}
+
+#[test]
+fn run_all() {
+    let directory: TempDir = TempDir::new("run-all");
+    for (program, golden) in [("eof_cat", "cat"), ("hello_world", "hello_world")] {
+        write(
+            directory.join(format!("{}.b", golden)),
+            read(Path::new(PROGRAMS).join(format!("{}.b", program))).unwrap(),
+        )
+        .unwrap();
+    }
+    write(directory.join("broken.b"), b"+[").unwrap();
+
+    let output: Output = Command::new(BRAINFUCK)
+        .arg("run-all")
+        .arg(directory.path())
+        .arg("--inputs")
+        .arg(Path::new(PROGRAMS).join("expected"))
+        .args(["--eof", "zero", "--json"])
+        .output()
+        .unwrap();
+    let summary: String = String::from_utf8(output.stdout).unwrap();
+    assert!(!output.status.success(), "broken.b passed:\n{}", summary);
+
+    let lines: Vec<&str> = summary.lines().collect();
+    assert_eq!(lines.len(), 5usize, "{}", summary);
+    assert!(
+        lines[1].contains("broken.b") && lines[1].contains("unmatched `[`"),
+        "{}",
+        summary
+    );
+    // The hashes are 64-bit FNV-1a of each program's output.
+    for (line, golden) in lines[2..4].iter().zip(["cat", "hello_world"]) {
+        let expected: Vec<u8> = read(
+            Path::new(PROGRAMS)
+                .join("expected")
+                .join(format!("{}.out", golden)),
+        )
+        .unwrap();
+        let hash: u64 = expected.iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3u64)
+        });
+        assert!(
+            line.contains("\"error\": null") && line.contains(&format!("{:016x}", hash)),
+            "{} differs from expected/{}.out:\n{}",
+            golden,
+            golden,
+            summary
+        );
+    }
+}
+
+#[test]
+fn vm() {
+    let dialect: Dialect = Dialect::default();
+    let tape_config: TapeConfig = TapeConfig {
+        length: 16usize,
+        growable: false,
+        max_length: 16usize,
+        bounds: Bounds::Error,
+    };
+    let io_config: IoConfig = IoConfig {
+        mode: IoMode::Raw,
+        eof: Eof::Zero,
+    };
+
+    let program: Program = frontend::parse(b">,[.,]", &dialect).unwrap();
+    let mut vm: Vm<u8, &[u8], Vec<u8>> = Vm::new(
+        &program,
+        &tape_config,
+        &io_config,
+        b"cat".as_slice(),
+        Vec::new(),
+    )
+    .unwrap();
+    vm.run().unwrap();
+    assert_eq!(vm.pointer(), 1usize);
+    assert_eq!(vm.tape(), [0u8; 16]);
+    assert_eq!(vm.into_writer(), b"cat");
+
+    // The leading `[-]` is optimized away, which only holds if every run starts on a clear tape.
+    let program: Program = frontend::parse(b"[-]+", &dialect).unwrap();
+    let mut vm: Vm<u8, &[u8], Vec<u8>> = Vm::new(
+        &program,
+        &tape_config,
+        &io_config,
+        b"".as_slice(),
+        Vec::new(),
+    )
+    .unwrap();
+    for _ in 0..2 {
+        vm.run().unwrap();
+        assert_eq!(vm.tape()[0], 1u8);
+    }
+}
+
+#[test]
+fn brainfuck() {
+    check("brainfuck", "brainfuck", &[]);
+}
+
+#[test]
+fn cat() {
+    // `cat.b` itself never stops, so its golden output is checked with one that ends at EOF.
+    check("eof_cat", "cat", &["--eof", "zero"]);
+}
+
+#[test]
+fn hanoi() {
+    check("hanoi", "hanoi", &[]);
+}
+
+#[test]
+fn hello_world() {
+    check("hello_world", "hello_world", &[]);
+}
+
+#[test]
+fn life() {
+    check("life", "life", &[]);
+}
+
+#[test]
+fn mandelbrot() {
+    check("mandelbrot", "mandelbrot", &[]);
+}
+
+#[test]
+fn printable() {
+    check("printable", "printable", &[]);
+}
+
+#[test]
+fn printable_raw() {
+    check("printable", "printable_raw", &["--raw"]);
+}
+
+#[test]
+fn shortest_hello_world() {
+    check("shortest_hello_world", "shortest_hello_world", &[]);
+}
+
+#[test]
+fn test() {
+    check("test", "test", &[]);
+}